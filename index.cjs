@@ -1,6 +1,8 @@
 const { platform, arch } = require('os')
 const { join } = require('path')
 const { readFileSync } = require('fs')
+const { threadId } = require('worker_threads')
+const { AsyncLocalStorage } = require('async_hooks')
 
 function isMusl() {
   if (process.report?.getReport) {
@@ -39,6 +41,8 @@ try {
   }
 }
 
+native.registerThread(threadId)
+
 /**
  * Sanitizes JS values before passing to native code to prevent crashes.
  * Handles: circular refs, NaN, Infinity, BigInt, Error objects.
@@ -120,16 +124,178 @@ function sanitize(value, seen = new WeakSet()) {
   }
 }
 
+// Shared by the ESM and CommonJS entry points, so context set through one is
+// seen by code logging through the other.
+const contextStorage = (globalThis[Symbol.for("eventum.context")] ??= new AsyncLocalStorage());
+let openTelemetry = false;
+let otelApi;
+let debugSignal = false;
+let reopenSignal = false;
+
+/**
+ * Trace and span ids of the active OpenTelemetry span, if the API package is
+ * installed and a valid span is active. The package is loaded on first use.
+ */
+function activeSpanContext() {
+  if (otelApi === undefined) {
+    try {
+      otelApi = require("@opentelemetry/api");
+    } catch {
+      otelApi = null;
+    }
+  }
+  const spanContext = otelApi?.trace.getActiveSpan()?.spanContext();
+  if (!spanContext || !otelApi.isSpanContextValid(spanContext)) {
+    return undefined;
+  }
+  return { traceId: spanContext.traceId, spanId: spanContext.spanId };
+}
+
+/**
+ * Fields attached to every entry logged from the current async context.
+ * Explicit context takes precedence over the active span. Passed to the native
+ * logger as a function, which calls it only for entries that pass the level
+ * filter and sampling.
+ */
+function currentContext() {
+  const store = contextStorage.getStore();
+  const span = openTelemetry ? activeSpanContext() : undefined;
+  if (!store && !span) {
+    return undefined;
+  }
+  return sanitize({ ...span, ...store });
+}
+
+function runWithContext(fields, fn) {
+  return contextStorage.run({ ...contextStorage.getStore(), ...fields }, fn);
+}
+
+/**
+ * Copy-on-write: async branches started before this call keep the store they
+ * were started with, so concurrent requests never see each other's fields.
+ */
+function setContext(fields) {
+  contextStorage.enterWith({ ...contextStorage.getStore(), ...fields });
+}
+
+function getContext() {
+  return { ...contextStorage.getStore() };
+}
+
+function onDebugSignal() {
+  native.toggleDebugLevel();
+}
+
+/**
+ * Installs or removes the SIGUSR2 listener that toggles debug levels.
+ * Windows has no SIGUSR2, so the option is ignored there.
+ */
+function watchDebugSignal(enabled) {
+  if (enabled === debugSignal || process.platform === "win32") {
+    return;
+  }
+  if (enabled) {
+    process.on("SIGUSR2", onDebugSignal);
+  } else {
+    process.off("SIGUSR2", onDebugSignal);
+  }
+  debugSignal = enabled;
+}
+
+function onReopenSignal() {
+  native.reopen();
+}
+
+/**
+ * Installs or removes the SIGHUP listener that reopens the log file after
+ * external rotation. Windows has no SIGHUP, so the option is ignored there.
+ */
+function watchReopenSignal(enabled) {
+  if (enabled === reopenSignal || process.platform === "win32") {
+    return;
+  }
+  if (enabled) {
+    process.on("SIGHUP", onReopenSignal);
+  } else {
+    process.off("SIGHUP", onReopenSignal);
+  }
+  reopenSignal = enabled;
+}
+
+/**
+ * Thrown by setConfig() and loadConfig() for an invalid config. `errors` and
+ * `warnings` list each problem with the path of the field, e.g. `prod.output.batchSize`.
+ */
+class ConfigValidationError extends Error {
+  constructor(message, errors, warnings) {
+    super(message);
+    this.name = "ConfigValidationError";
+    this.code = "ERR_INVALID_CONFIG";
+    this.errors = errors;
+    this.warnings = warnings;
+  }
+}
+
+/**
+ * Applies a config natively, then its JavaScript side.
+ */
+function track(apply) {
+  let applied;
+  try {
+    applied = apply();
+  } catch (err) {
+    if (err?.code === "ERR_INVALID_CONFIG") {
+      throw new ConfigValidationError(err.message, err.errors, err.warnings);
+    }
+    throw err;
+  }
+  openTelemetry = applied.context?.openTelemetry ?? false;
+  watchDebugSignal(applied.debugSignal ?? false);
+  watchReopenSignal(applied.output.reopenSignal ?? false);
+  return applied;
+}
+
+function setConfig(config) {
+  return track(() => native.setConfig(config));
+}
+
+function loadConfig(path) {
+  return track(() => native.loadConfig(path));
+}
+
 module.exports = {
-  trace: (message) => native.trace(sanitize(message)),
-  info: (message) => native.info(sanitize(message)),
-  debug: (message) => native.debug(sanitize(message)),
-  warn: (message) => native.warn(sanitize(message)),
-  error: (message) => native.error(sanitize(message)),
-  fatal: (message) => native.fatal(sanitize(message)),
+  trace: (message) => native.trace(sanitize(message), currentContext),
+  info: (message) => native.info(sanitize(message), currentContext),
+  debug: (message) => native.debug(sanitize(message), currentContext),
+  warn: (message) => native.warn(sanitize(message), currentContext),
+  error: (message) => native.error(sanitize(message), currentContext),
+  fatal: (message) => native.fatal(sanitize(message), currentContext),
+  log: (level, message) => native.log(level, sanitize(message), currentContext),
+  getContext,
+  runWithContext,
+  setContext,
+  getLevel: native.getLevel,
+  setLevel: native.setLevel,
+  toggleDebugLevel: native.toggleDebugLevel,
+  getSinkFailures: native.getSinkFailures,
+  reopen: native.reopen,
+  rotate: native.rotate,
+  onRotate: native.onRotate,
   shutdown: native.shutdown,
-  setConfig: native.setConfig,
+  setConfig,
+  loadConfig,
+  validateConfig: native.validateConfig,
+  ConfigValidationError,
+  setThreadName: native.setThreadName,
+  ColorMode: native.ColorMode,
+  FsyncPolicy: native.FsyncPolicy,
+  HttpBodyFormat: native.HttpBodyFormat,
   LogLevel: native.LogLevel,
+  OtlpEncoding: native.OtlpEncoding,
   OutputFormat: native.OutputFormat,
   OutputTarget: native.OutputTarget,
+  RotateReason: native.RotateReason,
+  RotationTimezone: native.RotationTimezone,
+  SyslogFormat: native.SyslogFormat,
+  SyslogProtocol: native.SyslogProtocol,
 };
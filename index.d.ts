@@ -3,13 +3,33 @@
 
 /* auto-generated by NAPI-RS */
 
-export declare function setConfig(config: LoggerConfig): EnvConfig | null
+export declare function setConfig(config: LoggerConfig): EnvConfig
+export declare function loadConfig(path: string): EnvConfig
+export declare function validateConfig(config: LoggerConfig): ValidationResult
+export declare class ConfigValidationError extends Error {
+  name: 'ConfigValidationError'
+  code: 'ERR_INVALID_CONFIG'
+  errors: Array<ConfigIssue>
+  warnings: Array<ConfigIssue>
+}
 export declare function trace(message: any): void
 export declare function info(message: any): void
 export declare function debug(message: any): void
 export declare function warn(message: any): void
 export declare function error(message: any): void
 export declare function fatal(message: any): void
+export declare function log(level: string, message: any): void
+export declare function runWithContext<T>(fields: Record<string, any>, fn: () => T): T
+export declare function setContext(fields: Record<string, any>): void
+export declare function getContext(): Record<string, any>
+export declare function setLevel(level: string, scope?: string | undefined | null): void
+export declare function getLevel(scope?: string | undefined | null): string | null
+export declare function toggleDebugLevel(): boolean
+export declare function reopen(): void
+export declare function rotate(): boolean
+export declare function onRotate(callback: ((event: RotateEvent) => void) | null): void
+export declare function getSinkFailures(): Record<string, number>
+export declare function setThreadName(name?: string | undefined | null): void
 export declare function shutdown(): void
 export const enum LogLevel {
   Trace = 0,
@@ -23,17 +43,23 @@ export const enum OutputTarget {
   Stdout = 0,
   Stderr = 1,
   File = 2,
-  Null = 3
+  Null = 3,
+  Syslog = 4,
+  Http = 5,
+  Tcp = 6,
+  UnixSocket = 7,
+  Journald = 8,
+  Otlp = 9
 }
 export const enum OutputFormat {
   Text = 0,
-  Json = 1
+  Json = 1,
+  Logfmt = 2
 }
-export interface LogEntry {
-  level: LogLevel
-  msg: any
-  time: number
-  pid: number
+export const enum ColorMode {
+  Never = 'never',
+  Always = 'always',
+  Auto = 'auto'
 }
 export interface MaskingConfig {
   keyword?: string
@@ -41,30 +67,193 @@ export interface MaskingConfig {
   partial?: Array<string>
   regex?: Array<string>
 }
+export const enum SyslogProtocol {
+  Unix = 'unix',
+  Udp = 'udp',
+  Tcp = 'tcp'
+}
+export const enum SyslogFormat {
+  Rfc5424 = 'rfc5424',
+  Rfc3164 = 'rfc3164'
+}
+export const enum RotateReason {
+  Size = 'size',
+  Interval = 'interval',
+  Hourly = 'hourly',
+  Daily = 'daily',
+  Weekly = 'weekly',
+  Manual = 'manual'
+}
+export interface RotateEvent {
+  oldPath: string
+  newPath: string
+  reason: RotateReason
+}
+export const enum RotationTimezone {
+  Utc = 'utc',
+  Local = 'local'
+}
+export const enum FsyncPolicy {
+  Never = 'never',
+  EveryBatch = 'everyBatch',
+  Interval = 'interval',
+  OnLevel = 'onLevel'
+}
+export const enum HttpBodyFormat {
+  Json = 'json',
+  Ndjson = 'ndjson'
+}
+export const enum OtlpEncoding {
+  Protobuf = 'protobuf',
+  Json = 'json'
+}
+export interface ColorTheme {
+  trace?: string
+  debug?: string
+  info?: string
+  warn?: string
+  error?: string
+  fatal?: string
+  time?: string
+  scope?: string
+  key?: string
+  value?: string
+}
+export interface SyslogConfig {
+  protocol?: SyslogProtocol
+  address?: string
+  format?: SyslogFormat
+  facility?: string
+  appName?: string
+  msgid?: string
+}
+export interface HttpConfig {
+  url: string
+  headers?: Record<string, string>
+  bodyFormat?: HttpBodyFormat
+  gzip?: boolean
+  timeoutMs?: number
+  maxRetries?: number
+  retryBaseMs?: number
+  retryMaxMs?: number
+  maxPendingRetries?: number
+}
+export interface StreamConfig {
+  address: string
+  tls?: boolean
+  tlsServerName?: string
+  tlsCaFile?: string
+  connectTimeoutMs?: number
+  reconnectBaseMs?: number
+  reconnectMaxMs?: number
+  spillBufferSize?: number
+}
+export interface JournaldConfig {
+  identifier?: string
+  socketPath?: string
+}
+export interface OtlpConfig {
+  endpoint?: string
+  encoding?: OtlpEncoding
+  headers?: Record<string, string>
+  gzip?: boolean
+  timeoutMs?: number
+  maxRetries?: number
+  resourceAttributes?: Record<string, string>
+}
 export interface OutputConfig {
-  color?: boolean
+  color?: boolean | ColorMode
+  theme?: ColorTheme
   format: OutputFormat
   target: OutputTarget
   filePath?: string
   maxFileSize?: number
   maxBackups?: number
+  maxAgeDays?: number
+  maxTotalSize?: number
   rotateDaily?: boolean
+  rotateHourly?: boolean
+  rotateWeekly?: boolean
+  rotateEveryMinutes?: number
+  rotationTimezone?: RotationTimezone
+  filePattern?: string
+  reopenSignal?: boolean
+  fileMode?: number
+  dirMode?: number
+  currentLink?: boolean
+  fsync?: FsyncPolicy
+  fsyncIntervalMs?: number
+  fsyncLevel?: string
   batchEnabled?: boolean
   batchSize?: number
   batchIntervalMs?: number
   masking?: MaskingConfig
+  syslog?: SyslogConfig
+  http?: HttpConfig
+  stream?: StreamConfig
+  journald?: JournaldConfig
+  otlp?: OtlpConfig
+  fallback?: Array<OutputTarget>
+  fallbackCooldownMs?: number
 }
 export interface FieldsConfig {
   pid?: boolean
   time?: boolean
   msg?: boolean
   level?: boolean
+  hostname?: boolean
+  threadId?: boolean
+  threadName?: boolean
+  caller?: boolean
+}
+export interface BaseConfig {
+  service?: string
+  version?: string
+  env?: string
+  region?: string
+}
+export interface ContextConfig {
+  openTelemetry?: boolean
+}
+export interface SamplingConfig {
+  sample?: Record<string, number>
+  scopes?: Record<string, number>
+  maxPerSecond?: number
+  dedupeWindowMs?: number
+}
+export interface CustomLevel {
+  name: string
+  severity: number
+  color?: string
 }
 export interface EnvConfig {
   output: OutputConfig
   fields?: FieldsConfig
+  base?: BaseConfig
+  level?: string
+  levels?: string
+  debugSignal?: boolean
+  customLevels?: Array<CustomLevel>
+  context?: ContextConfig
+  sampling?: SamplingConfig
+}
+export interface ConfigIssue {
+  path: string
+  message: string
+}
+export interface ValidationResult {
+  valid: boolean
+  errors: Array<ConfigIssue>
+  warnings: Array<ConfigIssue>
+}
+export interface NamedEnvConfig extends Partial<Omit<EnvConfig, 'output'>> {
+  extends?: string
+  output?: Partial<OutputConfig>
 }
 export interface LoggerConfig {
   dev?: EnvConfig
   prod?: EnvConfig
+  environments?: Record<string, NamedEnvConfig>
+  env?: string
+  envVar?: string
 }
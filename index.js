@@ -3,6 +3,8 @@ import { createRequire } from 'node:module'
 import { fileURLToPath } from 'node:url'
 import { dirname, join } from 'node:path'
 import { readFileSync } from 'node:fs'
+import { threadId } from 'node:worker_threads'
+import { AsyncLocalStorage } from 'node:async_hooks'
 
 const __dirname = dirname(fileURLToPath(import.meta.url))
 const require = createRequire(import.meta.url)
@@ -44,6 +46,8 @@ try {
   }
 }
 
+native.registerThread(threadId)
+
 /**
  * Sanitizes JS values before passing to native code to prevent crashes.
  * Handles: circular refs, NaN, Infinity, BigInt, Error objects.
@@ -125,19 +129,177 @@ function sanitize(value, seen = new WeakSet()) {
   }
 }
 
-export const trace = (message) => native.trace(sanitize(message));
-export const info = (message) => native.info(sanitize(message));
-export const debug = (message) => native.debug(sanitize(message));
-export const warn = (message) => native.warn(sanitize(message));
-export const error = (message) => native.error(sanitize(message));
-export const fatal = (message) => native.fatal(sanitize(message));
+// Shared by the ESM and CommonJS entry points, so context set through one is
+// seen by code logging through the other.
+const contextStorage = (globalThis[Symbol.for("eventum.context")] ??= new AsyncLocalStorage());
+let openTelemetry = false;
+let otelApi;
+let debugSignal = false;
+let reopenSignal = false;
+
+/**
+ * Trace and span ids of the active OpenTelemetry span, if the API package is
+ * installed and a valid span is active. The package is loaded on first use.
+ */
+function activeSpanContext() {
+  if (otelApi === undefined) {
+    try {
+      otelApi = require("@opentelemetry/api");
+    } catch {
+      otelApi = null;
+    }
+  }
+  const spanContext = otelApi?.trace.getActiveSpan()?.spanContext();
+  if (!spanContext || !otelApi.isSpanContextValid(spanContext)) {
+    return undefined;
+  }
+  return { traceId: spanContext.traceId, spanId: spanContext.spanId };
+}
+
+/**
+ * Fields attached to every entry logged from the current async context.
+ * Explicit context takes precedence over the active span. Passed to the native
+ * logger as a function, which calls it only for entries that pass the level
+ * filter and sampling.
+ */
+function currentContext() {
+  const store = contextStorage.getStore();
+  const span = openTelemetry ? activeSpanContext() : undefined;
+  if (!store && !span) {
+    return undefined;
+  }
+  return sanitize({ ...span, ...store });
+}
+
+function runWithContext(fields, fn) {
+  return contextStorage.run({ ...contextStorage.getStore(), ...fields }, fn);
+}
+
+/**
+ * Copy-on-write: async branches started before this call keep the store they
+ * were started with, so concurrent requests never see each other's fields.
+ */
+function setContext(fields) {
+  contextStorage.enterWith({ ...contextStorage.getStore(), ...fields });
+}
+
+function getContext() {
+  return { ...contextStorage.getStore() };
+}
+
+function onDebugSignal() {
+  native.toggleDebugLevel();
+}
+
+/**
+ * Installs or removes the SIGUSR2 listener that toggles debug levels.
+ * Windows has no SIGUSR2, so the option is ignored there.
+ */
+function watchDebugSignal(enabled) {
+  if (enabled === debugSignal || process.platform === "win32") {
+    return;
+  }
+  if (enabled) {
+    process.on("SIGUSR2", onDebugSignal);
+  } else {
+    process.off("SIGUSR2", onDebugSignal);
+  }
+  debugSignal = enabled;
+}
+
+function onReopenSignal() {
+  native.reopen();
+}
+
+/**
+ * Installs or removes the SIGHUP listener that reopens the log file after
+ * external rotation. Windows has no SIGHUP, so the option is ignored there.
+ */
+function watchReopenSignal(enabled) {
+  if (enabled === reopenSignal || process.platform === "win32") {
+    return;
+  }
+  if (enabled) {
+    process.on("SIGHUP", onReopenSignal);
+  } else {
+    process.off("SIGHUP", onReopenSignal);
+  }
+  reopenSignal = enabled;
+}
+
+/**
+ * Thrown by setConfig() and loadConfig() for an invalid config. `errors` and
+ * `warnings` list each problem with the path of the field, e.g. `prod.output.batchSize`.
+ */
+class ConfigValidationError extends Error {
+  constructor(message, errors, warnings) {
+    super(message);
+    this.name = "ConfigValidationError";
+    this.code = "ERR_INVALID_CONFIG";
+    this.errors = errors;
+    this.warnings = warnings;
+  }
+}
+
+/**
+ * Applies a config natively, then its JavaScript side.
+ */
+function track(apply) {
+  let applied;
+  try {
+    applied = apply();
+  } catch (err) {
+    if (err?.code === "ERR_INVALID_CONFIG") {
+      throw new ConfigValidationError(err.message, err.errors, err.warnings);
+    }
+    throw err;
+  }
+  openTelemetry = applied.context?.openTelemetry ?? false;
+  watchDebugSignal(applied.debugSignal ?? false);
+  watchReopenSignal(applied.output.reopenSignal ?? false);
+  return applied;
+}
+
+function setConfig(config) {
+  return track(() => native.setConfig(config));
+}
+
+function loadConfig(path) {
+  return track(() => native.loadConfig(path));
+}
+
+export const trace = (message) => native.trace(sanitize(message), currentContext);
+export const info = (message) => native.info(sanitize(message), currentContext);
+export const debug = (message) => native.debug(sanitize(message), currentContext);
+export const warn = (message) => native.warn(sanitize(message), currentContext);
+export const error = (message) => native.error(sanitize(message), currentContext);
+export const fatal = (message) => native.fatal(sanitize(message), currentContext);
+export const log = (level, message) => native.log(level, sanitize(message), currentContext);
+
+export { ConfigValidationError, getContext, loadConfig, runWithContext, setConfig, setContext };
 
 export const {
+  ColorMode,
+  FsyncPolicy,
+  HttpBodyFormat,
   LogLevel,
+  OtlpEncoding,
   OutputFormat,
   OutputTarget,
+  RotateReason,
+  RotationTimezone,
+  SyslogFormat,
+  SyslogProtocol,
+  getLevel,
+  getSinkFailures,
+  onRotate,
+  reopen,
+  rotate,
   shutdown,
-  setConfig,
+  setLevel,
+  setThreadName,
+  toggleDebugLevel,
+  validateConfig,
 } = native;
 
 export default native;
\ No newline at end of file
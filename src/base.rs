@@ -0,0 +1,89 @@
+use crate::format::push_pair;
+use crate::types::EnvConfig;
+
+#[derive(Clone, Debug, Default)]
+pub struct BaseFields {
+    pub hostname: Option<String>,
+    pub service: Option<String>,
+    pub version: Option<String>,
+    pub env: Option<String>,
+    pub region: Option<String>,
+    // Rendered once here so formatting an entry only copies them.
+    pub text: String,
+    pub logfmt: String,
+}
+
+impl From<&EnvConfig> for BaseFields {
+    fn from(config: &EnvConfig) -> Self {
+        let fields = config.fields.clone().unwrap_or_default();
+        let base = config.base.clone().unwrap_or_default();
+
+        let hostname = fields
+            .hostname
+            .unwrap_or(false)
+            .then(|| gethostname::gethostname().to_string_lossy().into_owned());
+
+        let mut base_fields = BaseFields {
+            hostname,
+            service: base.service,
+            version: base.version,
+            env: base.env,
+            region: base.region,
+            text: String::new(),
+            logfmt: String::new(),
+        };
+        base_fields.text = base_fields.render_text();
+        base_fields.logfmt = base_fields.render_logfmt();
+
+        base_fields
+    }
+}
+
+impl BaseFields {
+    // Whether `key` is written at the top level of every JSON entry.
+    pub fn emits(&self, key: &str) -> bool {
+        let value = match key {
+            "hostname" => &self.hostname,
+            "service" => &self.service,
+            "version" => &self.version,
+            "env" => &self.env,
+            "region" => &self.region,
+            _ => return false,
+        };
+        value.is_some()
+    }
+
+    fn render_text(&self) -> String {
+        let pairs: Vec<String> = self
+            .pairs("host")
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+
+        if pairs.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", pairs.join(" "))
+        }
+    }
+
+    // Keys match the JSON output, unlike the shorter `host` of text output.
+    fn render_logfmt(&self) -> String {
+        let mut output = String::new();
+        for (key, value) in self.pairs("hostname") {
+            push_pair(&mut output, key, value);
+        }
+        output
+    }
+
+    fn pairs<'a>(&'a self, host_key: &'a str) -> impl Iterator<Item = (&'a str, &'a str)> {
+        [
+            (host_key, &self.hostname),
+            ("service", &self.service),
+            ("version", &self.version),
+            ("env", &self.env),
+            ("region", &self.region),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.as_deref().map(|value| (key, value)))
+    }
+}
@@ -0,0 +1,104 @@
+use napi::{Env, JsString};
+use once_cell::sync::Lazy;
+use std::iter::Filter;
+use std::str::Lines;
+use std::sync::RwLock;
+
+use crate::types::CallerLocation;
+
+// Files of the JS wrappers (index.js, index.cjs) that have been loaded. Their
+// frames sit between the user's call site and the native call, except when
+// the native functions are called directly through the default export.
+static WRAPPER_FILES: Lazy<RwLock<Vec<String>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+fn stack_frames(env: &Env) -> Option<String> {
+    let error = env
+        .create_error(napi::Error::from_reason(String::new()))
+        .ok()?;
+    error
+        .get_named_property::<JsString>("stack")
+        .ok()?
+        .into_utf8()
+        .ok()?
+        .into_owned()
+        .ok()
+}
+
+// A concrete type rather than `impl Iterator`, so the iterator can be used in
+// a tail expression that borrows a local.
+fn frames(stack: &str) -> Filter<Lines<'_>, fn(&&str) -> bool> {
+    stack
+        .lines()
+        .filter(|line| line.trim_start().starts_with("at "))
+}
+
+// Called from the top level of a wrapper as it loads, so the first frame is
+// the wrapper itself.
+pub fn register_wrapper(env: &Env) {
+    let Some(stack) = stack_frames(env) else {
+        return;
+    };
+    let Some((_, file, _)) = frames(&stack).next().and_then(split_frame) else {
+        return;
+    };
+    let mut files = WRAPPER_FILES.write().expect("Wrapper files lock poisoned");
+    if !files.iter().any(|known| known == file) {
+        files.push(file.to_string());
+    }
+}
+
+pub fn capture_caller(env: &Env) -> Option<CallerLocation> {
+    let stack = stack_frames(env)?;
+    let wrappers = WRAPPER_FILES.read().expect("Wrapper files lock poisoned");
+
+    frames(&stack)
+        .find(|line| {
+            split_frame(line).is_some_and(|(_, file, _)| !wrappers.iter().any(|w| w == file))
+        })
+        .and_then(parse_frame)
+}
+
+// Splits a V8 stack frame into its function, file and line.
+fn split_frame(line: &str) -> Option<(Option<&str>, &str, u32)> {
+    let frame = line.trim_start().strip_prefix("at ")?;
+    let frame = frame.strip_prefix("async ").unwrap_or(frame);
+
+    let (function, location) = match frame.rfind(" (") {
+        Some(idx) if frame.ends_with(')') => {
+            (Some(&frame[..idx]), &frame[idx + 2..frame.len() - 1])
+        }
+        _ => (None, frame),
+    };
+
+    let mut parts = location.rsplitn(3, ':');
+    let _column = parts.next()?;
+    let line = parts.next()?.parse::<u32>().ok()?;
+    let file = parts.next()?;
+    Some((function, file, line))
+}
+
+// Parses a V8 stack frame such as `at handle (file:///srv/app/src/app.js:42:13)`
+// or `at /srv/app/src/app.js:42:13`.
+pub fn parse_frame(line: &str) -> Option<CallerLocation> {
+    let (function, file, line) = split_frame(line)?;
+
+    Some(CallerLocation {
+        file: relative_path(file),
+        line,
+        function: function.map(str::to_string),
+    })
+}
+
+fn relative_path(file: &str) -> String {
+    let file = file.strip_prefix("file://").unwrap_or(file);
+
+    std::env::current_dir()
+        .ok()
+        .and_then(|cwd| {
+            std::path::Path::new(file)
+                .strip_prefix(cwd)
+                .ok()
+                .map(|p| p.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| file.to_string())
+}
@@ -1,6 +1,14 @@
+use crate::base::BaseFields;
+use crate::directives::ActiveLevels;
+use crate::levels::LevelRegistry;
 use crate::masking::MaskRule;
-use crate::types::{EnvConfig, WorkerMsg};
-use once_cell::sync::OnceCell;
+use crate::sampling::Sampler;
+use crate::theme::Palette;
+use crate::types::{EnvConfig, RotateEvent, WorkerMsg};
+use arc_swap::ArcSwap;
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction};
+use once_cell::sync::{Lazy, OnceCell};
+use std::sync::atomic::AtomicBool;
 use std::sync::mpsc::Sender;
 use std::sync::{Mutex, RwLock};
 use std::thread;
@@ -11,4 +19,25 @@ pub static SENDER: OnceCell<Mutex<Option<Sender<WorkerMsg>>>> = OnceCell::new();
 
 pub static MASKING_RULES: OnceCell<RwLock<MaskRule>> = OnceCell::new();
 
+pub static BASE_FIELDS: OnceCell<RwLock<BaseFields>> = OnceCell::new();
+
+pub static PALETTE: OnceCell<RwLock<Option<Palette>>> = OnceCell::new();
+
+pub static CALLER_ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub static LEVELS: OnceCell<RwLock<LevelRegistry>> = OnceCell::new();
+
+// Read on every log call, so it is swapped rather than locked.
+pub static LEVEL_FILTER: Lazy<ArcSwap<ActiveLevels>> =
+    Lazy::new(|| ArcSwap::from_pointee(ActiveLevels::default()));
+
+pub static SAMPLING_ENABLED: AtomicBool = AtomicBool::new(false);
+
+// Read on every log call while sampling is enabled, so it is swapped rather than locked.
+pub static SAMPLER: Lazy<ArcSwap<Sampler>> =
+    Lazy::new(|| ArcSwap::from_pointee(Sampler::default()));
+
 pub static BATCH_THREAD: OnceCell<Mutex<Option<thread::JoinHandle<()>>>> = OnceCell::new();
+
+pub static ON_ROTATE: Mutex<Option<ThreadsafeFunction<RotateEvent, ErrorStrategy::Fatal>>> =
+    Mutex::new(None);
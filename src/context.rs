@@ -0,0 +1,66 @@
+use serde_json::{Map, Value};
+
+use crate::base::BaseFields;
+use crate::masking::MaskRule;
+
+// Keys Eventum already writes at the top level of a JSON entry. Context fields
+// with these names are dropped so they cannot shadow or duplicate them.
+const RESERVED_KEYS: [&str; 8] = [
+    "level",
+    "msg",
+    "time",
+    "pid",
+    "threadId",
+    "threadName",
+    "caller",
+    "scope",
+];
+
+// Base fields (`service`, `env`, ...) only shadow context fields of the same
+// name when they are emitted. Masking applies as it does to the message.
+pub fn context_fields(
+    context: Map<String, Value>,
+    base: &BaseFields,
+    masking: Option<&MaskRule>,
+) -> Option<Map<String, Value>> {
+    let fields: Map<String, Value> = context
+        .into_iter()
+        .filter(|(key, value)| {
+            !RESERVED_KEYS.contains(&key.as_str()) && !base.emits(key) && !value.is_null()
+        })
+        .collect();
+    if fields.is_empty() {
+        return None;
+    }
+
+    match masking.map(|rule| rule.mask(&Value::Object(fields.clone()))) {
+        Some(Value::Object(masked)) => Some(masked),
+        _ => Some(fields),
+    }
+}
+
+pub fn render_text(context: &Map<String, Value>) -> String {
+    let pairs: Vec<String> = context
+        .iter()
+        .map(|(key, value)| match value {
+            Value::String(s) => format!("{}={}", key, s),
+            other => format!("{}={}", key, other),
+        })
+        .collect();
+
+    format!(" [{}]", pairs.join(" "))
+}
+
+// Decodes a W3C trace or span id (32 or 16 hex characters). All-zero ids are invalid.
+pub fn hex_id(value: &Value, bytes: usize) -> Option<Vec<u8>> {
+    let hex = value.as_str()?;
+    if hex.len() != bytes * 2 {
+        return None;
+    }
+
+    let id = (0..bytes)
+        .map(|i| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+
+    id.iter().any(|b| *b != 0).then_some(id)
+}
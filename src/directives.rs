@@ -0,0 +1,292 @@
+use once_cell::sync::Lazy;
+use std::borrow::Cow;
+use std::sync::{Arc, Mutex};
+
+use crate::config::LEVEL_FILTER;
+use crate::levels::{Level, LevelRegistry};
+use crate::types::{EnvConfig, LogLevel};
+
+// Overrides `EnvConfig.levels`, the same way RUST_LOG overrides a compiled-in filter.
+pub const ENV_VAR: &str = "EVENTUM_LOG";
+
+const OFF: Level = Level {
+    name: Cow::Borrowed("off"),
+    severity: u32::MAX,
+};
+
+// Serializes writers; readers go through LEVEL_FILTER without locking.
+static UPDATE: Mutex<()> = Mutex::new(());
+
+// The filter that was active before SIGUSR2 switched to debug, if it did.
+static SAVED: Lazy<Mutex<Option<LevelFilter>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Clone, Debug)]
+struct Directive {
+    pattern: String,
+    level: Level,
+}
+
+// `*` matches any run of characters; everything else is compared literally.
+pub fn scope_matches(pattern: &str, scope: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = scope.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+// Exact scopes beat wildcards; among wildcards the longest literal part wins.
+pub fn specificity(pattern: &str) -> (bool, usize) {
+    (
+        !pattern.contains('*'),
+        pattern.chars().filter(|c| *c != '*').count(),
+    )
+}
+
+impl Directive {
+    fn matches(&self, scope: &str) -> bool {
+        scope_matches(&self.pattern, scope)
+    }
+
+    fn specificity(&self) -> (bool, usize) {
+        specificity(&self.pattern)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LevelFilter {
+    default: Option<Level>,
+    directives: Vec<Directive>,
+}
+
+pub fn parse_level(name: &str, levels: &LevelRegistry) -> Result<Level, String> {
+    if name.eq_ignore_ascii_case("off") {
+        return Ok(OFF);
+    }
+    levels
+        .lookup(name)
+        .ok_or_else(|| format!("'{}' is not a known log level", name))
+}
+
+impl LevelFilter {
+    // Parses `info,AuthService=debug,Payment*=trace`. A bare level sets the
+    // default for entries whose scope matches no directive.
+    pub fn parse(
+        spec: &str,
+        default: Option<Level>,
+        levels: &LevelRegistry,
+    ) -> Result<Self, String> {
+        let mut filter = LevelFilter {
+            default,
+            directives: Vec::new(),
+        };
+
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                None => filter.default = Some(parse_level(directive, levels)?),
+                Some((pattern, level)) => {
+                    let pattern = pattern.trim();
+                    if pattern.is_empty() {
+                        return Err(format!("directive '{}' has an empty scope", directive));
+                    }
+                    filter.set(parse_level(level.trim(), levels)?, Some(pattern));
+                }
+            }
+        }
+
+        Ok(filter)
+    }
+
+    // Sets the default level, or the level of one scope pattern. A pattern that
+    // is already present is replaced, so the last directive for it wins.
+    pub fn set(&mut self, level: Level, scope: Option<&str>) {
+        let Some(pattern) = scope else {
+            self.default = Some(level);
+            return;
+        };
+
+        self.directives
+            .retain(|directive| directive.pattern != pattern);
+        let directive = Directive {
+            pattern: pattern.to_string(),
+            level,
+        };
+        // Keep the most specific directives first; among equals the newest goes first and wins.
+        let index = self
+            .directives
+            .iter()
+            .position(|existing| existing.specificity() <= directive.specificity())
+            .unwrap_or(self.directives.len());
+        self.directives.insert(index, directive);
+    }
+
+    // Every threshold capped at debug, so debug entries pass for all scopes.
+    // Scopes turned `off` stay off.
+    fn capped_at_debug(&self) -> LevelFilter {
+        let debug = Level::from(LogLevel::Debug);
+        let cap = |level: &Level| {
+            if level.severity > debug.severity && level.severity != OFF.severity {
+                debug.clone()
+            } else {
+                level.clone()
+            }
+        };
+
+        LevelFilter {
+            default: Some(self.default.as_ref().map_or(debug.clone(), cap)),
+            directives: self
+                .directives
+                .iter()
+                .map(|directive| Directive {
+                    pattern: directive.pattern.clone(),
+                    level: cap(&directive.level),
+                })
+                .collect(),
+        }
+    }
+
+    fn has_directives(&self) -> bool {
+        !self.directives.is_empty()
+    }
+
+    // The lowest severity any scope can log at, used for the cheap first check.
+    fn floor(&self) -> u32 {
+        self.directives
+            .iter()
+            .map(|directive| directive.level.severity)
+            .fold(self.severity_for(None), u32::min)
+    }
+
+    // The threshold for a scope; `None` when no minimum applies.
+    pub fn level_for(&self, scope: Option<&str>) -> Option<&Level> {
+        scope
+            .and_then(|scope| {
+                self.directives
+                    .iter()
+                    .find(|directive| directive.matches(scope))
+            })
+            .map(|directive| &directive.level)
+            .or(self.default.as_ref())
+    }
+
+    pub fn severity_for(&self, scope: Option<&str>) -> u32 {
+        self.level_for(scope).map_or(0, |level| level.severity)
+    }
+}
+
+// The filter together with what log() checks before consulting it, published
+// as one snapshot so a reader never pairs a new floor with an old filter.
+#[derive(Debug, Default)]
+pub struct ActiveLevels {
+    floor: u32,
+    scoped: bool,
+    pub filter: LevelFilter,
+}
+
+impl From<LevelFilter> for ActiveLevels {
+    fn from(filter: LevelFilter) -> Self {
+        ActiveLevels {
+            floor: filter.floor(),
+            scoped: filter.has_directives(),
+            filter,
+        }
+    }
+}
+
+impl ActiveLevels {
+    // The scope is only looked up when some directive could apply to it.
+    pub fn enabled<'a>(&self, level: &Level, scope: impl FnOnce() -> Option<&'a str>) -> bool {
+        level.severity >= self.floor
+            && (!self.scoped || level.severity >= self.filter.severity_for(scope()))
+    }
+}
+
+// `EnvConfig.level` is the default, `EnvConfig.levels` refines it, and a valid
+// EVENTUM_LOG replaces `levels` altogether.
+pub fn level_filter(config: &EnvConfig, levels: &LevelRegistry) -> LevelFilter {
+    let default = config.level.as_deref().and_then(|name| levels.lookup(name));
+
+    if let Ok(spec) = std::env::var(ENV_VAR) {
+        match LevelFilter::parse(&spec, default.clone(), levels) {
+            Ok(filter) => return filter,
+            Err(e) => eprintln!("[Logger] Ignoring {}: {}.", ENV_VAR, e),
+        }
+    }
+
+    // Validation rejects an invalid `levels`; should one get through, say so
+    // rather than silently dropping every directive.
+    if let Some(spec) = config.levels.as_deref() {
+        match LevelFilter::parse(spec, default.clone(), levels) {
+            Ok(filter) => return filter,
+            Err(e) => eprintln!("[Logger] Ignoring levels: {}.", e),
+        }
+    }
+
+    LevelFilter {
+        default,
+        directives: Vec::new(),
+    }
+}
+
+fn store(filter: LevelFilter) {
+    LEVEL_FILTER.store(Arc::new(ActiveLevels::from(filter)));
+}
+
+// Replaces the filter wholesale, as `setConfig()` does.
+pub fn install(filter: LevelFilter) {
+    let _guard = UPDATE.lock().expect("Level update mutex poisoned");
+    *SAVED.lock().expect("Saved levels mutex poisoned") = None;
+    store(filter);
+}
+
+// While debug is toggled on, the change goes to the saved levels, so toggling
+// back keeps it; it applies right away wherever it is finer than debug.
+pub fn update(level: Level, scope: Option<&str>) {
+    let _guard = UPDATE.lock().expect("Level update mutex poisoned");
+    let mut saved = SAVED.lock().expect("Saved levels mutex poisoned");
+
+    match saved.as_mut() {
+        Some(previous) => {
+            previous.set(level, scope);
+            store(previous.capped_at_debug());
+        }
+        None => {
+            let mut filter = LEVEL_FILTER.load().filter.clone();
+            filter.set(level, scope);
+            store(filter);
+        }
+    }
+}
+
+// Switches every scope to at least debug, or back to the levels in effect
+// before. Returns whether debug is now on.
+pub fn toggle_debug() -> bool {
+    let _guard = UPDATE.lock().expect("Level update mutex poisoned");
+    let mut saved = SAVED.lock().expect("Saved levels mutex poisoned");
+
+    match saved.take() {
+        Some(previous) => {
+            store(previous);
+            false
+        }
+        None => {
+            let current = LEVEL_FILTER.load().filter.clone();
+            store(current.capped_at_debug());
+            *saved = Some(current);
+            true
+        }
+    }
+}
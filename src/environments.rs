@@ -0,0 +1,172 @@
+use serde_json::{Map, Value};
+
+use crate::types::{ConfigIssue, EnvConfig, LoggerConfig};
+
+const DEFAULT_ENV_VAR: &str = "NODE_ENV";
+
+// NODE_ENV values conventionally used for the `dev` and `prod` configs.
+const ALIASES: [(&str, &str); 2] = [("production", "prod"), ("development", "dev")];
+
+// Picks the config for the current environment, along with the path of its
+// section (`prod`, `environments.staging`) for reporting issues. Without
+// `environments`, `env` or `envVar`, `prod` is used when NODE_ENV is
+// `production` and `dev` (or else `prod`) otherwise.
+pub fn select(config: LoggerConfig) -> Result<(String, EnvConfig), ConfigIssue> {
+    if config.environments.is_some() || config.env.is_some() || config.env_var.is_some() {
+        return select_named(config);
+    }
+    match std::env::var(DEFAULT_ENV_VAR).as_deref() {
+        Ok("production") => config
+            .prod
+            .map(|env| ("prod".to_string(), env))
+            .ok_or_else(|| ConfigIssue::new("prod", "must be set when NODE_ENV is production")),
+        _ => match (config.dev, config.prod) {
+            (Some(env), _) => Ok(("dev".to_string(), env)),
+            (None, Some(env)) => Ok(("prod".to_string(), env)),
+            (None, None) => Err(ConfigIssue::new("dev", "must be set, or prod")),
+        },
+    }
+}
+
+fn select_named(config: LoggerConfig) -> Result<(String, EnvConfig), ConfigIssue> {
+    let var = config.env_var.as_deref().unwrap_or(DEFAULT_ENV_VAR);
+    // Where the name came from, which is what to fix when it is wrong.
+    let (source, name) = match config.env {
+        Some(name) => ("env".to_string(), name),
+        None => match std::env::var(var) {
+            Ok(name) => (var.to_string(), name),
+            Err(_) => {
+                return Err(ConfigIssue::new(
+                    "env",
+                    format!("must be set when the {} variable is not", var),
+                ))
+            }
+        },
+    };
+    let named: Vec<String> = config
+        .environments
+        .iter()
+        .flatten()
+        .map(|(name, _)| name.clone())
+        .collect();
+
+    // `dev` and `prod` are environments too, unless `environments` redefines them.
+    let mut environments: Map<String, Value> = config
+        .environments
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    for (key, env) in [("dev", config.dev), ("prod", config.prod)] {
+        if let Some(env) = env.filter(|_| !environments.contains_key(key)) {
+            let value =
+                serde_json::to_value(env).map_err(|err| ConfigIssue::new(key, err.to_string()))?;
+            environments.insert(key.to_string(), value);
+        }
+    }
+
+    let name = if environments.contains_key(&name) {
+        name
+    } else {
+        ALIASES
+            .iter()
+            .find(|(alias, key)| *alias == name && environments.contains_key(*key))
+            .map_or(name, |(_, key)| key.to_string())
+    };
+
+    if !environments.contains_key(&name) {
+        return Err(ConfigIssue::new(
+            source,
+            format!(
+                "'{}' is not a defined environment ({})",
+                name,
+                available(&environments)
+            ),
+        ));
+    }
+
+    let section = section(&named, &name);
+    let resolved = resolve(&environments, &named, &name, &mut Vec::new())?;
+    let env = serde_json::from_value(resolved)
+        .map_err(|err| ConfigIssue::new(section.clone(), err.to_string()))?;
+    Ok((section, env))
+}
+
+// Top-level `dev` and `prod` keep their own path unless `environments` redefines them.
+fn section(named: &[String], name: &str) -> String {
+    if named.iter().any(|defined| defined == name) {
+        format!("environments.{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+fn available(environments: &Map<String, Value>) -> String {
+    if environments.is_empty() {
+        return "none are defined".to_string();
+    }
+    let mut names: Vec<&str> = environments.keys().map(String::as_str).collect();
+    names.sort_unstable();
+    format!("available: {}", names.join(", "))
+}
+
+// The environment with everything it extends merged in, base first.
+fn resolve(
+    environments: &Map<String, Value>,
+    named: &[String],
+    name: &str,
+    chain: &mut Vec<String>,
+) -> Result<Value, ConfigIssue> {
+    let section = section(named, name);
+    let mut env = environments[name].clone();
+    let Some(fields) = env.as_object_mut() else {
+        return Err(ConfigIssue::new(section, "must be an object"));
+    };
+    let extends = format!("{}.extends", section);
+    let parent = match fields.remove("extends") {
+        None | Some(Value::Null) => None,
+        Some(Value::String(parent)) => Some(parent),
+        Some(_) => return Err(ConfigIssue::new(extends, "must be a string")),
+    };
+
+    chain.push(name.to_string());
+    let Some(parent) = parent else {
+        return Ok(env);
+    };
+    if chain.contains(&parent) {
+        return Err(ConfigIssue::new(
+            extends,
+            format!("forms a cycle: {} -> {}", chain.join(" -> "), parent),
+        ));
+    }
+    if !environments.contains_key(&parent) {
+        return Err(ConfigIssue::new(
+            extends,
+            format!(
+                "'{}' is not a defined environment ({})",
+                parent,
+                available(environments)
+            ),
+        ));
+    }
+
+    let mut base = resolve(environments, named, &parent, chain)?;
+    merge(&mut base, env);
+    Ok(base)
+}
+
+// JSON Merge Patch (RFC 7396): objects are merged key by key, `null` removes a
+// key and anything else, arrays included, replaces the base value.
+fn merge(base: &mut Value, patch: Value) {
+    match (base, patch) {
+        (Value::Object(base), Value::Object(patch)) => {
+            for (key, value) in patch {
+                if value.is_null() {
+                    base.remove(&key);
+                } else {
+                    merge(base.entry(key).or_insert(Value::Null), value);
+                }
+            }
+        }
+        (base, patch) => *base = patch,
+    }
+}
@@ -0,0 +1,78 @@
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::types::OutputTarget;
+
+pub const DEFAULT_COOLDOWN_MS: i64 = 30_000;
+
+#[derive(Default)]
+struct Breaker {
+    failures: u32,
+    open_until: Option<Instant>,
+}
+
+// Health of every sink that has failed at least once, keyed by target.
+static BREAKERS: Lazy<Mutex<HashMap<OutputTarget, Breaker>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub fn target_name(target: OutputTarget) -> &'static str {
+    match target {
+        OutputTarget::Stdout => "stdout",
+        OutputTarget::Stderr => "stderr",
+        OutputTarget::File => "file",
+        OutputTarget::Null => "null",
+        OutputTarget::Syslog => "syslog",
+        OutputTarget::Http => "http",
+        OutputTarget::Tcp => "tcp",
+        OutputTarget::UnixSocket => "unixSocket",
+        OutputTarget::Journald => "journald",
+        OutputTarget::Otlp => "otlp",
+    }
+}
+
+// A sink whose circuit is open is skipped until its cool-down has passed; the
+// next write after that is a trial that closes the circuit on success.
+pub fn is_open(target: OutputTarget) -> bool {
+    let breakers = BREAKERS.lock().expect("Breakers mutex poisoned");
+    breakers
+        .get(&target)
+        .and_then(|breaker| breaker.open_until)
+        .is_some_and(|until| Instant::now() < until)
+}
+
+pub fn record_failure(target: OutputTarget, reason: &str, cooldown: Option<Duration>) {
+    let mut breakers = BREAKERS.lock().expect("Breakers mutex poisoned");
+    let breaker = breakers.entry(target).or_default();
+    breaker.failures = breaker.failures.saturating_add(1);
+
+    if let Some(cooldown) = cooldown {
+        if breaker.open_until.is_none() {
+            eprintln!(
+                "[Logger] Output {} failed: {}. Using fallback for {}ms.",
+                target_name(target),
+                reason,
+                cooldown.as_millis()
+            );
+        }
+        breaker.open_until = Some(Instant::now() + cooldown);
+    }
+}
+
+pub fn record_success(target: OutputTarget) {
+    let mut breakers = BREAKERS.lock().expect("Breakers mutex poisoned");
+    if let Some(breaker) = breakers.get_mut(&target) {
+        if breaker.open_until.take().is_some() {
+            eprintln!("[Logger] Output {} recovered.", target_name(target));
+        }
+    }
+}
+
+pub fn failure_counts() -> HashMap<String, u32> {
+    let breakers = BREAKERS.lock().expect("Breakers mutex poisoned");
+    breakers
+        .iter()
+        .map(|(target, breaker)| (target_name(*target).to_string(), breaker.failures))
+        .collect()
+}
@@ -0,0 +1,350 @@
+use napi::threadsafe_function::ThreadsafeFunctionCallMode;
+use once_cell::sync::OnceCell;
+use std::fs::{self, DirBuilder, File, Metadata, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::config::{LEVELS, ON_ROTATE};
+use crate::levels::Level;
+use crate::rotation::{self, current_path, rotate_now, size_limit, take_rotations};
+use crate::types::{FsyncPolicy, LogEntry, LogLevel, OutputConfig};
+
+// PIPE_BUF on Linux: POSIX guarantees that writes up to this size are not
+// interleaved with other writers even on a pipe or FIFO.
+const ATOMIC_WRITE_SIZE: usize = 4096;
+const DEFAULT_FSYNC_INTERVAL_MS: i64 = 1000;
+
+static FILE: OnceCell<Mutex<Option<FileSink>>> = OnceCell::new();
+
+// The log file, kept open between writes.
+struct FileSink {
+    path: PathBuf,
+    file: File,
+    // Identity of the open file, compared with whatever `path` names now.
+    metadata: Metadata,
+    // Bytes known to be in the file. A shorter file was truncated.
+    len: u64,
+    // Whether data was written since the last sync, under a policy that syncs.
+    dirty: bool,
+    synced: Instant,
+}
+
+#[cfg(unix)]
+fn same_file(a: &Metadata, b: &Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    a.dev() == b.dev() && a.ino() == b.ino()
+}
+
+// Without inode numbers there is no cheap identity check, so the file is
+// reopened for every write, as if it had been replaced.
+#[cfg(not(unix))]
+fn same_file(_: &Metadata, _: &Metadata) -> bool {
+    false
+}
+
+fn create_dirs(output: &OutputConfig, dir: &Path) -> io::Result<()> {
+    let mut builder = DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    if let Some(mode) = output.dir_mode {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(mode);
+    }
+    builder.create(dir)
+}
+
+// Points the `currentLink` at `target`. The link is created next to it and
+// renamed over the old one, so readers never see it missing.
+#[cfg(unix)]
+fn update_link(link: &Path, target: &Path) {
+    let (Some(link_name), Some(target_name)) = (link.file_name(), target.file_name()) else {
+        return;
+    };
+    if link == target || link_name == target_name {
+        return;
+    }
+    if fs::symlink_metadata(link).is_ok_and(|metadata| !metadata.file_type().is_symlink()) {
+        eprintln!(
+            "[Logger] Not replacing {} with a link: it is not a symlink.",
+            link.display()
+        );
+        return;
+    }
+
+    let staged = link.with_file_name(format!(".{}.link", link_name.to_string_lossy()));
+    let _ = fs::remove_file(&staged);
+    if std::os::unix::fs::symlink(target_name, &staged).is_ok()
+        && fs::rename(&staged, link).is_err()
+    {
+        let _ = fs::remove_file(&staged);
+    }
+}
+
+// Creating symlinks needs extra privileges on Windows, so there is no link there.
+#[cfg(not(unix))]
+fn update_link(_: &Path, _: &Path) {}
+
+// Opens a log file for appending. Missing parent directories are created with
+// `dirMode` and a missing file with `fileMode`; the process umask applies to both.
+pub fn open_log_file(output: &OutputConfig, path: &Path) -> Result<File, String> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        if !dir.exists() {
+            create_dirs(output, dir).map_err(|err| {
+                format!("failed to create log directory {}: {}", dir.display(), err)
+            })?;
+        }
+    }
+
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    if let Some(mode) = output.file_mode {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(mode);
+    }
+    let file = options
+        .open(path)
+        .map_err(|err| format!("failed to open log file {}: {}", path.display(), err))?;
+
+    if output.current_link.unwrap_or(false) {
+        if let Some(link) = output.file_path.as_deref() {
+            update_link(Path::new(link), path);
+        }
+    }
+
+    Ok(file)
+}
+
+impl FileSink {
+    fn open(output: &OutputConfig, path: &Path) -> Result<Self, String> {
+        let file = open_log_file(output, path)?;
+        let metadata = file
+            .metadata()
+            .map_err(|err| format!("failed to stat log file {}: {}", path.display(), err))?;
+
+        Ok(FileSink {
+            path: path.to_path_buf(),
+            file,
+            len: metadata.len(),
+            metadata,
+            dirty: false,
+            synced: Instant::now(),
+        })
+    }
+
+    // False once the file was renamed or deleted (logrotate `create`, or our own
+    // size rotation) or truncated (logrotate `copytruncate`).
+    fn is_current(&self, path: &Path) -> bool {
+        self.path == path
+            && fs::metadata(path).is_ok_and(|metadata| {
+                same_file(&self.metadata, &metadata) && metadata.len() >= self.len
+            })
+    }
+
+    fn sync(&mut self) -> Result<(), String> {
+        self.dirty = false;
+        self.synced = Instant::now();
+        self.file
+            .sync_data()
+            .map_err(|err| format!("failed to sync log file {}: {}", self.path.display(), err))
+    }
+}
+
+// The open sink if it still writes to `path`, otherwise a new one. A replaced
+// sink is synced first if its policy asked for it.
+fn sink_for(
+    slot: &mut Option<FileSink>,
+    output: &OutputConfig,
+    path: &Path,
+) -> Result<FileSink, String> {
+    match slot.take() {
+        Some(sink) if sink.is_current(path) => Ok(sink),
+        Some(mut old) => {
+            if old.dirty {
+                let _ = old.sync();
+            }
+            FileSink::open(output, path)
+        }
+        None => FileSink::open(output, path),
+    }
+}
+
+// The leading lines written together: at most ATOMIC_WRITE_SIZE bytes unless a
+// single line is longer, and no more once the file reaches its rotation size.
+fn chunk(lines: &[String], len: u64, limit: Option<u64>) -> (usize, Vec<u8>) {
+    let mut bytes = Vec::with_capacity(ATOMIC_WRITE_SIZE);
+    let mut count = 0;
+    for line in lines {
+        let size = line.len() + 1;
+        let full = bytes.len() + size > ATOMIC_WRITE_SIZE
+            || limit.is_some_and(|max| len + bytes.len() as u64 >= max);
+        if count > 0 && full {
+            break;
+        }
+        bytes.extend_from_slice(line.as_bytes());
+        bytes.push(b'\n');
+        count += 1;
+    }
+    (count, bytes)
+}
+
+fn fsync_threshold(output: &OutputConfig) -> Level {
+    output
+        .fsync_level
+        .as_deref()
+        .and_then(|name| {
+            LEVELS
+                .get()?
+                .read()
+                .expect("Levels lock poisoned")
+                .lookup(name)
+        })
+        .unwrap_or_else(|| LogLevel::Error.into())
+}
+
+fn fsync_due(output: &OutputConfig, sink: &FileSink, entries: &[LogEntry]) -> bool {
+    match output.fsync {
+        None | Some(FsyncPolicy::Never) => false,
+        Some(FsyncPolicy::EveryBatch) => true,
+        Some(FsyncPolicy::Interval) => sink.synced.elapsed() >= fsync_interval(output),
+        Some(FsyncPolicy::OnLevel) => {
+            let threshold = fsync_threshold(output).severity;
+            entries
+                .iter()
+                .any(|entry| entry.level.severity >= threshold)
+        }
+    }
+}
+
+fn fsync_interval(output: &OutputConfig) -> Duration {
+    let ms = output
+        .fsync_interval_ms
+        .unwrap_or(DEFAULT_FSYNC_INTERVAL_MS);
+    Duration::from_millis(ms.max(1) as u64)
+}
+
+// Writes a batch in chunks of whole lines, one `write` call each. With
+// O_APPEND, such a write is never interleaved with writes from other processes
+// appending to the same file, e.g. cluster workers. On failure, returns how
+// many leading lines were written.
+pub fn file_output(
+    output: &OutputConfig,
+    entries: &[LogEntry],
+    lines: &[String],
+) -> Result<(), (usize, String)> {
+    let mut slot = lock();
+
+    let limit = size_limit(output);
+    let mut written = 0;
+    while written < lines.len() {
+        // Resolved per chunk so that size rotation still happens within a batch.
+        let path = current_path(output).map_err(|e| (written, e))?;
+        let mut sink = sink_for(&mut slot, output, &path).map_err(|e| (written, e))?;
+        notify_rotations();
+        let (count, bytes) = chunk(&lines[written..], sink.len, limit);
+
+        // On failure the handle is dropped, so the next write starts with a fresh one.
+        sink.file.write_all(&bytes).map_err(|err| {
+            (
+                written,
+                format!("failed to write to file {}: {}", path.display(), err),
+            )
+        })?;
+        sink.len += bytes.len() as u64;
+        sink.dirty = output
+            .fsync
+            .is_some_and(|fsync| fsync != FsyncPolicy::Never);
+        written += count;
+        *slot = Some(sink);
+    }
+
+    match slot.as_mut() {
+        // The lines are in the file either way, so a failed sync only reports an error.
+        Some(sink) if sink.dirty && fsync_due(output, sink, entries) => {
+            sink.sync().map_err(|e| (lines.len(), e))
+        }
+        _ => Ok(()),
+    }
+}
+
+// Reports rotations to the `onRotate()` callback. Called once the old file is
+// closed, so the callback can move or upload it right away.
+fn notify_rotations() {
+    let rotations = take_rotations();
+    if rotations.is_empty() {
+        return;
+    }
+    let callback = ON_ROTATE.lock().expect("Rotate callback mutex poisoned");
+    if let Some(callback) = callback.as_ref() {
+        for event in rotations {
+            callback.call(event, ThreadsafeFunctionCallMode::NonBlocking);
+        }
+    }
+}
+
+fn lock() -> MutexGuard<'static, Option<FileSink>> {
+    FILE.get_or_init(|| Mutex::new(None))
+        .lock()
+        .expect("File sink mutex poisoned")
+}
+
+// Starts the file of a new period from the writer thread's timer.
+pub fn rotate_if_due(output: &OutputConfig) {
+    let mut slot = lock();
+    if let Some(path) = rotation::rotate_if_due(output) {
+        if let Ok(sink) = sink_for(&mut slot, output, &path) {
+            *slot = Some(sink);
+        }
+        notify_rotations();
+    }
+}
+
+// Rotates on request. Returns false when there is no file to rotate yet.
+pub fn rotate(output: &OutputConfig) -> Result<bool, String> {
+    let mut slot = lock();
+    if !rotate_now(output)? {
+        return Ok(false);
+    }
+    let path = current_path(output)?;
+    let sink = sink_for(&mut slot, output, &path);
+    notify_rotations();
+    *slot = Some(sink?);
+    Ok(true)
+}
+
+// Syncs data left unsynced by the `interval` policy once the interval is over.
+pub fn sync_if_due(output: &OutputConfig) {
+    if output.fsync != Some(FsyncPolicy::Interval) {
+        return;
+    }
+    let Some(slot) = FILE.get() else {
+        return;
+    };
+    let mut slot = slot.lock().expect("File sink mutex poisoned");
+    if let Some(sink) = slot.as_mut().filter(|sink| sink.dirty) {
+        if sink.synced.elapsed() >= fsync_interval(output) {
+            let _ = sink.sync();
+        }
+    }
+}
+
+// Syncs whatever a policy other than `never` left unsynced, e.g. at shutdown.
+pub fn sync() {
+    let Some(slot) = FILE.get() else {
+        return;
+    };
+    let mut slot = slot.lock().expect("File sink mutex poisoned");
+    if let Some(sink) = slot.as_mut().filter(|sink| sink.dirty) {
+        let _ = sink.sync();
+    }
+}
+
+// Closes the log file. The next write opens `filePath` again, creating it if needed.
+pub fn reopen() {
+    sync();
+    if let Some(slot) = FILE.get() {
+        *slot.lock().expect("File sink mutex poisoned") = None;
+    }
+}
@@ -1,33 +1,87 @@
+use crate::context::render_text;
+use crate::theme::{Palette, Style};
+use crate::types::SerializableLogEntry;
 use crate::types::{EnvConfig, LogEntry};
-use crate::types::{LogLevel, SerializableLogEntry};
 use crate::utils::{
-    extract_scope_and_text, extract_scope_and_value, mask_message_if_needed, write_output,
+    current_palette, extract_scope_and_text, extract_scope_and_value, mask_message_if_needed,
+    with_base_fields,
 };
-use colored::Colorize;
+use serde_json::Value;
 
-pub fn format_log_text(entry: &LogEntry, config: &EnvConfig) {
+pub fn format_log_text(entry: &LogEntry, config: &EnvConfig) -> String {
+    render_text_entry(entry, config, false)
+}
+
+// For newline-framed outputs: objects are written as compact JSON and line
+// breaks inside strings are escaped.
+pub fn format_log_text_line(entry: &LogEntry, config: &EnvConfig) -> String {
+    render_text_entry(entry, config, true).replace('\n', "\\n")
+}
+
+fn render_text_entry(entry: &LogEntry, config: &EnvConfig, single_line: bool) -> String {
     let fields = config.fields.clone().unwrap_or_default();
+    let palette = current_palette();
+    let paint = |style: fn(&Palette) -> &Style, text: String| match &palette {
+        Some(palette) => style(palette).paint(&text),
+        None => text,
+    };
 
     let masked_msg = mask_message_if_needed(&entry.msg);
 
     let mut output = String::new();
 
     if fields.level.unwrap_or(false) {
-        output.push_str(&format!("[{:?}]", entry.level));
+        let badge = format!("[{}]", entry.level.name);
+        let style = palette.as_ref().and_then(|p| p.level(&entry.level));
+        output.push_str(&match style {
+            Some(style) => style.paint(&badge),
+            None => badge,
+        });
     }
 
     if fields.pid.unwrap_or(false) {
         output.push_str(&format!(" [PID:{}]", entry.pid));
     }
 
+    if fields.thread_id.unwrap_or(false) {
+        output.push_str(&format!(" [TID:{}]", entry.thread_id));
+    }
+
+    if fields.thread_name.unwrap_or(false) {
+        if let Some(name) = &entry.thread_name {
+            output.push_str(&format!(" [Thread:{}]", name));
+        }
+    }
+
     if fields.time.unwrap_or(false) {
-        output.push_str(&format!(" [{}]", entry.time));
+        output.push(' ');
+        output.push_str(&paint(|p| &p.time, format!("[{}]", entry.time)));
+    }
+
+    with_base_fields(|base| output.push_str(&base.text));
+
+    if let Some(context) = &entry.context {
+        output.push_str(&render_text(context));
+    }
+
+    if fields.caller.unwrap_or(false) {
+        if let Some(caller) = &entry.caller {
+            output.push_str(&format!(" ({}:{})", caller.file, caller.line));
+        }
     }
 
     if fields.msg.unwrap_or(true) {
-        let (scope, text) = extract_scope_and_text(&masked_msg);
+        let (scope, text) = match &palette {
+            Some(palette) if !single_line => {
+                let (scope, msg) = extract_scope_and_value(&masked_msg);
+                (scope, palette.paint_message(&msg))
+            }
+            _ => extract_scope_and_text(&masked_msg, single_line),
+        };
+
         if let Some(scope) = scope {
-            output.push_str(&format!(" [{}]", scope));
+            output.push(' ');
+            output.push_str(&paint(|p| &p.scope, format!("[{}]", scope)));
         }
 
         if !text.is_empty() {
@@ -35,39 +89,139 @@ pub fn format_log_text(entry: &LogEntry, config: &EnvConfig) {
         }
     }
 
-    let color = config.output.color.unwrap_or(false);
+    output
+}
 
-    let final_output = if color {
-        match entry.level {
-            LogLevel::Trace => output.bright_black().to_string(),
-            LogLevel::Debug => output.cyan().to_string(),
-            LogLevel::Info => output.green().to_string(),
-            LogLevel::Warn => output.yellow().to_string(),
-            LogLevel::Error => output.red().to_string(),
-            LogLevel::Fatal => output.bold().red().to_string(),
-        }
-    } else {
-        output
-    };
+pub fn format_log_json(entry: &LogEntry, config: &EnvConfig) -> String {
+    let fields = config.fields.clone().unwrap_or_default();
+    let masked_msg = mask_message_if_needed(&entry.msg);
+
+    let (scope, msg_without_scope) = extract_scope_and_value(&masked_msg);
 
-    write_output(&config, &final_output);
+    with_base_fields(|base| {
+        let filtered_entry = SerializableLogEntry {
+            level: fields.level.unwrap_or(false).then(|| entry.level.clone()),
+            msg: fields.msg.unwrap_or(true).then_some(msg_without_scope),
+            time: fields.time.unwrap_or(false).then_some(entry.time),
+            pid: fields.pid.unwrap_or(false).then_some(entry.pid),
+            thread_id: fields.thread_id.unwrap_or(false).then_some(entry.thread_id),
+            thread_name: fields
+                .thread_name
+                .unwrap_or(false)
+                .then(|| entry.thread_name.clone())
+                .flatten(),
+            hostname: base.hostname.as_deref(),
+            caller: fields
+                .caller
+                .unwrap_or(false)
+                .then(|| entry.caller.clone())
+                .flatten(),
+            service: base.service.as_deref(),
+            version: base.version.as_deref(),
+            env: base.env.as_deref(),
+            region: base.region.as_deref(),
+            scope,
+            context: entry.context.clone(),
+        };
+
+        serde_json::to_string(&filtered_entry).unwrap_or_default()
+    })
 }
 
-pub fn format_log_json(entry: &LogEntry, config: &EnvConfig) {
+// One `key=value` line with the keys of the JSON output, in the order of the
+// text output. Keys of an object message become pairs of their own.
+pub fn format_log_logfmt(entry: &LogEntry, config: &EnvConfig) -> String {
     let fields = config.fields.clone().unwrap_or_default();
     let masked_msg = mask_message_if_needed(&entry.msg);
 
-    let (scope, msg_without_scope) = extract_scope_and_value(&masked_msg);
+    let mut output = String::new();
 
-    let filtered_entry = SerializableLogEntry {
-        level: fields.level.unwrap_or(false).then_some(entry.level.clone()),
-        msg: fields.msg.unwrap_or(true).then_some(msg_without_scope),
-        time: fields.time.unwrap_or(false).then_some(entry.time),
-        pid: fields.pid.unwrap_or(false).then_some(entry.pid),
-        scope,
-    };
+    if fields.level.unwrap_or(false) {
+        push_pair(&mut output, "level", &entry.level.name);
+    }
 
-    if let Ok(json) = serde_json::to_string(&filtered_entry) {
-        write_output(config, &json);
+    if fields.pid.unwrap_or(false) {
+        push_pair(&mut output, "pid", &entry.pid.to_string());
+    }
+
+    if fields.thread_id.unwrap_or(false) {
+        push_pair(&mut output, "threadId", &entry.thread_id.to_string());
+    }
+
+    if fields.thread_name.unwrap_or(false) {
+        if let Some(name) = &entry.thread_name {
+            push_pair(&mut output, "threadName", name);
+        }
+    }
+
+    if fields.time.unwrap_or(false) {
+        push_pair(&mut output, "time", &entry.time.to_string());
+    }
+
+    with_base_fields(|base| {
+        if !base.logfmt.is_empty() {
+            if !output.is_empty() {
+                output.push(' ');
+            }
+            output.push_str(&base.logfmt);
+        }
+    });
+
+    for (key, value) in entry.context.iter().flatten() {
+        push_pair(&mut output, key, &logfmt_value(value));
+    }
+
+    if fields.caller.unwrap_or(false) {
+        if let Some(caller) = &entry.caller {
+            push_pair(
+                &mut output,
+                "caller",
+                &format!("{}:{}", caller.file, caller.line),
+            );
+        }
+    }
+
+    if fields.msg.unwrap_or(true) {
+        let (scope, msg) = extract_scope_and_value(&masked_msg);
+
+        if let Some(scope) = scope {
+            push_pair(&mut output, "scope", &scope);
+        }
+
+        match &msg {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    push_pair(&mut output, key, &logfmt_value(value));
+                }
+            }
+            other => push_pair(&mut output, "msg", &logfmt_value(other)),
+        }
+    }
+
+    output
+}
+
+// Strings as they are; anything else as compact JSON.
+fn logfmt_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+// Appends ` key=value`. Values with spaces, `=`, quotes or control characters
+// are quoted with JSON escaping; such characters in keys become `_`.
+pub fn push_pair(output: &mut String, key: &str, value: &str) {
+    let unsafe_char = |c: char| c == ' ' || c == '=' || c == '"' || c.is_control();
+
+    if !output.is_empty() {
+        output.push(' ');
+    }
+    output.extend(key.chars().map(|c| if unsafe_char(c) { '_' } else { c }));
+    output.push('=');
+    if value.is_empty() || value.chars().any(unsafe_char) {
+        output.push_str(&Value::from(value).to_string());
+    } else {
+        output.push_str(value);
     }
 }
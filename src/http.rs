@@ -0,0 +1,325 @@
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use once_cell::sync::OnceCell;
+use std::collections::{HashMap, VecDeque};
+use std::io::Write;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::types::{HttpBodyFormat, HttpConfig, OutputFormat};
+
+const DEFAULT_TIMEOUT_MS: i64 = 5_000;
+const DEFAULT_MAX_RETRIES: u32 = 5;
+const DEFAULT_RETRY_BASE_MS: i64 = 200;
+const DEFAULT_RETRY_MAX_MS: i64 = 30_000;
+const DEFAULT_MAX_PENDING_RETRIES: u32 = 10;
+
+// One sink per url, so changing the url or switching between the Http and Otlp
+// targets of a fallback chain keeps each endpoint's retry queue.
+static HTTP: OnceCell<Mutex<HashMap<String, HttpSink>>> = OnceCell::new();
+
+struct PendingBatch {
+    content_type: &'static str,
+    body: Vec<u8>,
+    entries: usize,
+    attempts: u32,
+    retry_at: Instant,
+}
+
+enum SendError {
+    // Network errors, timeouts, 408, 429 and 5xx responses.
+    Retryable(String),
+    Fatal(String),
+}
+
+struct HttpSink {
+    config: HttpConfig,
+    agent: ureq::Agent,
+    pending: VecDeque<PendingBatch>,
+}
+
+fn agent(config: &HttpConfig) -> ureq::Agent {
+    let timeout = config.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS).max(1) as u64;
+    ureq::AgentBuilder::new()
+        .timeout(Duration::from_millis(timeout))
+        .build()
+}
+
+impl HttpSink {
+    fn new(config: HttpConfig) -> Self {
+        HttpSink {
+            agent: agent(&config),
+            config,
+            pending: VecDeque::new(),
+        }
+    }
+
+    // Takes the latest settings for this url. Queued batches are kept.
+    fn reconfigure(&mut self, config: &HttpConfig) {
+        if self.config.timeout_ms != config.timeout_ms {
+            self.agent = agent(config);
+        }
+        self.config = config.clone();
+    }
+
+    // `timeout` overrides the configured `timeoutMs`, to fit a request in
+    // what is left of the shutdown drain.
+    fn send(
+        &self,
+        content_type: &str,
+        body: &[u8],
+        timeout: Option<Duration>,
+    ) -> Result<(), SendError> {
+        let mut request = self
+            .agent
+            .post(&self.config.url)
+            .set("Content-Type", content_type);
+        if let Some(timeout) = timeout {
+            request = request.timeout(timeout);
+        }
+        if self.config.gzip.unwrap_or(false) {
+            request = request.set("Content-Encoding", "gzip");
+        }
+        for (name, value) in self.config.headers.iter().flatten() {
+            request = request.set(name, value);
+        }
+
+        match request.send_bytes(body) {
+            Ok(_) => Ok(()),
+            Err(ureq::Error::Status(code, _)) if code == 408 || code == 429 || code >= 500 => {
+                Err(SendError::Retryable(format!("HTTP {}", code)))
+            }
+            Err(ureq::Error::Status(code, _)) => Err(SendError::Fatal(format!("HTTP {}", code))),
+            Err(err) => Err(SendError::Retryable(err.to_string())),
+        }
+    }
+
+    fn backoff(&self, attempts: u32) -> Duration {
+        let base = self
+            .config
+            .retry_base_ms
+            .unwrap_or(DEFAULT_RETRY_BASE_MS)
+            .max(1) as u64;
+        let max = self
+            .config
+            .retry_max_ms
+            .unwrap_or(DEFAULT_RETRY_MAX_MS)
+            .max(1) as u64;
+        let delay = base.saturating_mul(1 << attempts.min(20)).min(max);
+
+        // "Equal jitter": half fixed, half random, so retries from several
+        // processes spread out without collapsing to zero.
+        Duration::from_millis(delay / 2 + fastrand::u64(0..=delay / 2))
+    }
+
+    // With `queue` unset, failures are returned to the caller (which has a
+    // fallback sink) instead of being retried or dropped here.
+    fn deliver(
+        &mut self,
+        content_type: &'static str,
+        body: Vec<u8>,
+        entries: usize,
+        queue: bool,
+    ) -> Result<(), String> {
+        match self.send(content_type, &body, None) {
+            Ok(()) => {}
+            Err(SendError::Fatal(reason)) | Err(SendError::Retryable(reason)) if !queue => {
+                return Err(format!("failed to send to {}: {}", self.config.url, reason));
+            }
+            Err(SendError::Fatal(reason)) => drop_batch(&self.config.url, entries, &reason),
+            Err(SendError::Retryable(reason)) => {
+                let max_retries = self.config.max_retries.unwrap_or(DEFAULT_MAX_RETRIES);
+                if max_retries == 0 {
+                    drop_batch(&self.config.url, entries, &reason);
+                    return Ok(());
+                }
+
+                let max_pending = self
+                    .config
+                    .max_pending_retries
+                    .unwrap_or(DEFAULT_MAX_PENDING_RETRIES)
+                    .max(1) as usize;
+                while self.pending.len() >= max_pending {
+                    if let Some(oldest) = self.pending.pop_front() {
+                        drop_batch(&self.config.url, oldest.entries, "retry queue is full");
+                    }
+                }
+
+                self.pending.push_back(PendingBatch {
+                    content_type,
+                    body,
+                    entries,
+                    attempts: 1,
+                    retry_at: Instant::now() + self.backoff(0),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    // Retries queued batches whose backoff has elapsed. Stops at the first
+    // failure since the endpoint is most likely still down.
+    fn retry_pending(&mut self) {
+        let max_retries = self.config.max_retries.unwrap_or(DEFAULT_MAX_RETRIES);
+
+        while let Some(mut batch) = self.pending.pop_front() {
+            if batch.retry_at > Instant::now() {
+                self.pending.push_front(batch);
+                return;
+            }
+
+            match self.send(batch.content_type, &batch.body, None) {
+                Ok(()) => {}
+                Err(SendError::Fatal(reason)) => {
+                    drop_batch(&self.config.url, batch.entries, &reason)
+                }
+                Err(SendError::Retryable(reason)) => {
+                    if batch.attempts >= max_retries {
+                        drop_batch(&self.config.url, batch.entries, &reason);
+                    } else {
+                        batch.retry_at = Instant::now() + self.backoff(batch.attempts);
+                        batch.attempts += 1;
+                        self.pending.push_front(batch);
+                    }
+                    return;
+                }
+            }
+        }
+    }
+
+    // Shutdown: sends queued batches regardless of their backoff until one
+    // fails or `deadline` passes, then drops the rest with a single report.
+    fn drain_pending(&mut self, deadline: Instant) {
+        let reason = loop {
+            let Some(batch) = self.pending.pop_front() else {
+                return;
+            };
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                self.pending.push_front(batch);
+                break "shutdown timed out".to_string();
+            }
+
+            let timeout = Duration::from_millis(
+                self.config.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS).max(1) as u64,
+            )
+            .min(remaining);
+            match self.send(batch.content_type, &batch.body, Some(timeout)) {
+                Ok(()) => {}
+                Err(SendError::Fatal(reason)) => {
+                    drop_batch(&self.config.url, batch.entries, &reason)
+                }
+                Err(SendError::Retryable(reason)) => {
+                    self.pending.push_front(batch);
+                    break reason;
+                }
+            }
+        };
+
+        let entries = self.pending.drain(..).map(|batch| batch.entries).sum();
+        drop_batch(&self.config.url, entries, &reason);
+    }
+}
+
+fn drop_batch(url: &str, entries: usize, reason: &str) {
+    eprintln!(
+        "[Logger] Failed to send {} log entries to {}: {}. Entries dropped.",
+        entries, url, reason
+    );
+}
+
+pub fn encode_body(config: &HttpConfig, format: OutputFormat, lines: &[String]) -> Vec<u8> {
+    let body = match config.body_format.unwrap_or(HttpBodyFormat::Json) {
+        HttpBodyFormat::Ndjson => {
+            let mut body = lines.join("\n");
+            body.push('\n');
+            body
+        }
+        HttpBodyFormat::Json => {
+            let items: Vec<String> = match format {
+                OutputFormat::Json => lines.to_vec(),
+                OutputFormat::Text | OutputFormat::Logfmt => lines
+                    .iter()
+                    .map(|line| serde_json::Value::String(line.clone()).to_string())
+                    .collect(),
+            };
+            format!("[{}]", items.join(","))
+        }
+    };
+
+    compress_body(config, body.into_bytes())
+}
+
+// Gzips the body when `gzip` is enabled; send() sets the matching header.
+pub fn compress_body(config: &HttpConfig, body: Vec<u8>) -> Vec<u8> {
+    if config.gzip.unwrap_or(false) {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        if encoder.write_all(&body).is_ok() {
+            if let Ok(compressed) = encoder.finish() {
+                return compressed;
+            }
+        }
+    }
+
+    body
+}
+
+pub fn http_output(
+    config: &HttpConfig,
+    format: OutputFormat,
+    lines: &[String],
+    queue: bool,
+) -> Result<(), String> {
+    if lines.is_empty() {
+        return Ok(());
+    }
+
+    let content_type = match config.body_format.unwrap_or(HttpBodyFormat::Json) {
+        HttpBodyFormat::Json => "application/json",
+        HttpBodyFormat::Ndjson => "application/x-ndjson",
+    };
+
+    post_batch(
+        config,
+        content_type,
+        encode_body(config, format, lines),
+        lines.len(),
+        queue,
+    )
+}
+
+// Sends one already encoded batch through the shared HTTP sink, queueing it
+// for retry on failure. Used by the Http target and by exporters built on it.
+pub fn post_batch(
+    config: &HttpConfig,
+    content_type: &'static str,
+    body: Vec<u8>,
+    entries: usize,
+    queue: bool,
+) -> Result<(), String> {
+    let cell = HTTP.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut sinks = cell.lock().expect("HTTP sink mutex poisoned");
+
+    let sink = sinks
+        .entry(config.url.clone())
+        .or_insert_with(|| HttpSink::new(config.clone()));
+    sink.reconfigure(config);
+
+    sink.retry_pending();
+    sink.deliver(content_type, body, entries, queue)
+}
+
+// Retries batches whose backoff has elapsed, for every endpoint. With
+// `drain_by` (shutdown) it sends everything it can before that instant and
+// drops the rest.
+pub fn retry_pending_http(drain_by: Option<Instant>) {
+    if let Some(cell) = HTTP.get() {
+        for sink in cell.lock().expect("HTTP sink mutex poisoned").values_mut() {
+            match drain_by {
+                Some(deadline) => sink.drain_pending(deadline),
+                None => sink.retry_pending(),
+            }
+        }
+    }
+}
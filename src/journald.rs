@@ -0,0 +1,413 @@
+#[cfg(unix)]
+use once_cell::sync::OnceCell;
+use serde_json::{Map, Value};
+#[cfg(unix)]
+use std::os::unix::net::UnixDatagram;
+
+use crate::base::BaseFields;
+use crate::syslog::syslog_severity;
+use crate::types::{JournaldConfig, LogEntry};
+use crate::utils::{extract_scope_and_value, mask_message_if_needed, with_base_fields};
+
+const DEFAULT_SOCKET_PATH: &str = "/run/systemd/journal/socket";
+const MAX_FIELD_NAME_LEN: usize = 64;
+// Size an entry is cut down to when it is too large for a single datagram,
+// and the most any one value keeps of it.
+const TRUNCATED_DATAGRAM_SIZE: usize = 64 * 1024;
+const TRUNCATED_VALUE_SIZE: usize = 8 * 1024;
+
+// Fields set by Eventum itself. Message keys that map onto one of these get a
+// MSG_ prefix so a log payload cannot override, say, the priority.
+const RESERVED_FIELDS: [&str; 13] = [
+    "MESSAGE",
+    "PRIORITY",
+    "SYSLOG_IDENTIFIER",
+    "SYSLOG_PID",
+    "LEVEL",
+    "CODE_FILE",
+    "CODE_LINE",
+    "CODE_FUNC",
+    "THREAD_NAME",
+    "SERVICE",
+    "VERSION",
+    "ENV",
+    "REGION",
+];
+
+#[cfg(unix)]
+static SOCKET: OnceCell<Option<UnixDatagram>> = OnceCell::new();
+
+// Turns an arbitrary key into a valid journal field name: uppercase ASCII
+// letters, digits and underscores, not starting with an underscore (reserved
+// for trusted fields) or a digit, and at most 64 characters long. camelCase
+// words are split, so `traceId` becomes TRACE_ID.
+pub fn field_name(key: &str) -> String {
+    let mut name = String::with_capacity(key.len());
+    let mut prev_lower = false;
+    for c in key.chars() {
+        match c {
+            'a'..='z' | '0'..='9' => name.push(c.to_ascii_uppercase()),
+            'A'..='Z' => {
+                if prev_lower {
+                    name.push('_');
+                }
+                name.push(c);
+            }
+            _ => name.push('_'),
+        }
+        prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+    }
+    let mut name = name.trim_start_matches('_').to_string();
+
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert_str(0, "FIELD_");
+    }
+    name.truncate(MAX_FIELD_NAME_LEN);
+    name
+}
+
+// Flattens nested objects into PARENT_CHILD fields. Arrays are kept as JSON.
+fn flatten(prefix: &str, value: &Value, out: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}_{}", prefix, key)
+                };
+                flatten(&key, value, out);
+            }
+        }
+        _ if prefix.is_empty() => {}
+        Value::Null => {}
+        Value::String(s) => out.push((field_name(prefix), s.clone())),
+        other => out.push((field_name(prefix), other.to_string())),
+    }
+}
+
+pub fn journal_fields(config: &JournaldConfig, entry: &LogEntry) -> Vec<(String, String)> {
+    let masked_msg = mask_message_if_needed(&entry.msg);
+    let (scope, msg_without_scope) = extract_scope_and_value(&masked_msg);
+
+    // Objects carry their text in `message`; the remaining keys become fields.
+    let (text, msg_fields) = match msg_without_scope {
+        Value::Object(mut map) => match map.remove("message") {
+            Some(Value::String(message)) => (message, map),
+            Some(other) => {
+                map.insert("message".to_string(), other);
+                (Value::Object(map.clone()).to_string(), map)
+            }
+            None => (Value::Object(map.clone()).to_string(), map),
+        },
+        Value::String(message) => (message, Map::new()),
+        other => (other.to_string(), Map::new()),
+    };
+
+    with_base_fields(|base| entry_fields(config, entry, scope, text, msg_fields, base))
+}
+
+fn entry_fields(
+    config: &JournaldConfig,
+    entry: &LogEntry,
+    scope: Option<String>,
+    text: String,
+    msg_fields: Map<String, Value>,
+    base: &BaseFields,
+) -> Vec<(String, String)> {
+    let identifier = scope
+        .or_else(|| config.identifier.clone())
+        .or_else(|| base.service.clone())
+        .unwrap_or_else(|| "node".to_string());
+
+    let mut fields = vec![
+        ("MESSAGE".to_string(), text),
+        (
+            "PRIORITY".to_string(),
+            syslog_severity(&entry.level).to_string(),
+        ),
+        ("SYSLOG_IDENTIFIER".to_string(), identifier),
+        ("SYSLOG_PID".to_string(), entry.pid.to_string()),
+        ("LEVEL".to_string(), entry.level.name.to_string()),
+    ];
+
+    if let Some(caller) = &entry.caller {
+        fields.push(("CODE_FILE".to_string(), caller.file.clone()));
+        fields.push(("CODE_LINE".to_string(), caller.line.to_string()));
+        if let Some(function) = &caller.function {
+            fields.push(("CODE_FUNC".to_string(), function.clone()));
+        }
+    }
+
+    if let Some(name) = &entry.thread_name {
+        fields.push(("THREAD_NAME".to_string(), name.clone()));
+    }
+
+    for (name, value) in [
+        ("SERVICE", &base.service),
+        ("VERSION", &base.version),
+        ("ENV", &base.env),
+        ("REGION", &base.region),
+    ] {
+        if let Some(value) = value {
+            fields.push((name.to_string(), value.clone()));
+        }
+    }
+
+    let mut extra = Vec::new();
+    if let Some(context) = &entry.context {
+        flatten("", &Value::Object(context.clone()), &mut extra);
+    }
+    flatten("", &Value::Object(msg_fields), &mut extra);
+    for (name, value) in extra {
+        let name = if RESERVED_FIELDS.contains(&name.as_str()) {
+            field_name(&format!("MSG_{}", name))
+        } else {
+            name
+        };
+        fields.push((name, value));
+    }
+
+    fields
+}
+
+// Native journal protocol: `NAME=value\n`, or for values containing a newline
+// `NAME\n` followed by the value length as a little-endian u64, the value and `\n`.
+pub fn encode_fields(fields: &[(String, String)]) -> Vec<u8> {
+    let mut datagram = Vec::new();
+
+    for (name, value) in fields {
+        datagram.extend_from_slice(name.as_bytes());
+        if value.contains('\n') {
+            datagram.push(b'\n');
+            datagram.extend_from_slice(&(value.len() as u64).to_le_bytes());
+        } else {
+            datagram.push(b'=');
+        }
+        datagram.extend_from_slice(value.as_bytes());
+        datagram.push(b'\n');
+    }
+
+    datagram
+}
+
+// Fallback for entries larger than the socket accepts in one datagram. Values
+// are cut to TRUNCATED_VALUE_SIZE and fields are kept in order, Eventum's own
+// first, until TRUNCATED_DATAGRAM_SIZE is reached; the rest are left out.
+pub fn truncate_fields(fields: &[(String, String)]) -> Vec<(String, String)> {
+    let mut budget = TRUNCATED_DATAGRAM_SIZE;
+    let mut truncated = Vec::new();
+
+    for (name, value) in fields {
+        // `NAME` plus the worst-case framing: `\n`, a u64 length and `\n`.
+        let overhead = name.len() + 10;
+        if budget <= overhead {
+            break;
+        }
+        let mut end = value.len().min(TRUNCATED_VALUE_SIZE).min(budget - overhead);
+        while !value.is_char_boundary(end) {
+            end -= 1;
+        }
+        budget -= overhead + end;
+        truncated.push((name.clone(), value[..end].to_string()));
+    }
+
+    truncated
+}
+
+#[cfg(unix)]
+fn send(path: &str, datagram: &[u8]) -> std::io::Result<()> {
+    let socket = SOCKET
+        .get_or_init(|| UnixDatagram::unbound().ok())
+        .as_ref()
+        .ok_or_else(|| std::io::Error::other("could not create a Unix datagram socket"))?;
+
+    socket.send_to(datagram, path).map(|_| ())
+}
+
+#[cfg(not(unix))]
+fn send(_path: &str, _datagram: &[u8]) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "journald is not supported on this platform",
+    ))
+}
+
+#[cfg(unix)]
+fn is_oversized(err: &std::io::Error) -> bool {
+    err.raw_os_error() == Some(libc::EMSGSIZE)
+}
+
+#[cfg(not(unix))]
+fn is_oversized(_err: &std::io::Error) -> bool {
+    false
+}
+
+pub fn journald_output(config: &JournaldConfig, entry: &LogEntry) -> Result<(), String> {
+    let fields = journal_fields(config, entry);
+    let path = config.socket_path.as_deref().unwrap_or(DEFAULT_SOCKET_PATH);
+
+    let result = match send(path, &encode_fields(&fields)) {
+        Err(err) if is_oversized(&err) => send(path, &encode_fields(&truncate_fields(&fields))),
+        result => result,
+    };
+    result.map_err(|err| format!("failed to send to journald {}: {}", path, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::levels::Level;
+    use crate::types::LogLevel;
+    use serde_json::json;
+
+    fn entry(level: Level, msg: Value) -> LogEntry {
+        LogEntry {
+            level,
+            msg,
+            time: 0,
+            pid: 42,
+            thread_id: 0,
+            thread_name: None,
+            caller: None,
+            context: None,
+        }
+    }
+
+    fn field<'a>(fields: &'a [(String, String)], name: &str) -> Option<&'a str> {
+        fields
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    #[test]
+    fn maps_levels_to_syslog_priorities() {
+        let notice = Level {
+            name: "Notice".into(),
+            severity: 35,
+        };
+        let levels = [
+            (LogLevel::Trace.into(), "7"),
+            (LogLevel::Debug.into(), "7"),
+            (LogLevel::Info.into(), "6"),
+            (notice, "5"),
+            (LogLevel::Warn.into(), "4"),
+            (LogLevel::Error.into(), "3"),
+            (LogLevel::Fatal.into(), "2"),
+        ];
+
+        for (level, priority) in levels {
+            let name = level.name.to_string();
+            let fields = journal_fields(&JournaldConfig::default(), &entry(level, json!("hi")));
+            assert_eq!(field(&fields, "PRIORITY"), Some(priority), "{}", name);
+            assert_eq!(field(&fields, "LEVEL"), Some(name.as_str()));
+        }
+    }
+
+    #[test]
+    fn flattens_message_fields_into_upper_case_names() {
+        let msg = json!({
+            "scope": "payments",
+            "message": "Card declined",
+            "traceId": "abc",
+            "user": { "id": 7, "firstName": "Ada" },
+            "tags": ["a", "b"],
+            "note": null
+        });
+        let fields = journal_fields(
+            &JournaldConfig::default(),
+            &entry(LogLevel::Warn.into(), msg),
+        );
+
+        assert_eq!(field(&fields, "MESSAGE"), Some("Card declined"));
+        assert_eq!(field(&fields, "SYSLOG_IDENTIFIER"), Some("payments"));
+        assert_eq!(field(&fields, "SYSLOG_PID"), Some("42"));
+        assert_eq!(field(&fields, "TRACE_ID"), Some("abc"));
+        assert_eq!(field(&fields, "USER_ID"), Some("7"));
+        assert_eq!(field(&fields, "USER_FIRST_NAME"), Some("Ada"));
+        assert_eq!(field(&fields, "TAGS"), Some(r#"["a","b"]"#));
+        assert_eq!(field(&fields, "NOTE"), None);
+    }
+
+    #[test]
+    fn prefixes_message_keys_that_clash_with_own_fields() {
+        let msg = json!({ "message": "Retrying", "priority": "high", "level": "custom" });
+        let fields = journal_fields(
+            &JournaldConfig::default(),
+            &entry(LogLevel::Info.into(), msg),
+        );
+
+        assert_eq!(field(&fields, "PRIORITY"), Some("6"));
+        assert_eq!(field(&fields, "MSG_PRIORITY"), Some("high"));
+        assert_eq!(field(&fields, "LEVEL"), Some("Info"));
+        assert_eq!(field(&fields, "MSG_LEVEL"), Some("custom"));
+    }
+
+    #[test]
+    fn sanitizes_field_names() {
+        assert_eq!(field_name("traceId"), "TRACE_ID");
+        assert_eq!(field_name("http.status-code"), "HTTP_STATUS_CODE");
+        assert_eq!(field_name("_private"), "PRIVATE");
+        assert_eq!(field_name("2fa"), "FIELD_2FA");
+        assert_eq!(field_name(&"a".repeat(100)).len(), MAX_FIELD_NAME_LEN);
+    }
+
+    #[test]
+    fn encodes_values_with_newlines_with_their_length() {
+        let fields = vec![
+            ("MESSAGE".to_string(), "line 1\nline 2".to_string()),
+            ("PRIORITY".to_string(), "6".to_string()),
+        ];
+
+        let mut expected = b"MESSAGE\n".to_vec();
+        expected.extend_from_slice(&13u64.to_le_bytes());
+        expected.extend_from_slice(b"line 1\nline 2\nPRIORITY=6\n");
+        assert_eq!(encode_fields(&fields), expected);
+    }
+
+    #[test]
+    fn truncates_oversized_entries_to_fit_one_datagram() {
+        let mut fields = vec![
+            ("MESSAGE".to_string(), "é".repeat(TRUNCATED_DATAGRAM_SIZE)),
+            ("PRIORITY".to_string(), "3".to_string()),
+        ];
+        for i in 0..20 {
+            fields.push((format!("FIELD_{}", i), "x".repeat(TRUNCATED_VALUE_SIZE)));
+        }
+
+        let truncated = truncate_fields(&fields);
+
+        assert!(encode_fields(&truncated).len() <= TRUNCATED_DATAGRAM_SIZE);
+        assert_eq!(
+            field(&truncated, "MESSAGE").map(str::len),
+            Some(TRUNCATED_VALUE_SIZE)
+        );
+        assert_eq!(field(&truncated, "PRIORITY"), Some("3"));
+        assert!(truncated.len() < fields.len());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sends_a_truncated_entry_when_the_datagram_is_too_large() {
+        let path =
+            std::env::temp_dir().join(format!("eventum-journal-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let journal = UnixDatagram::bind(&path).unwrap();
+        let config = JournaldConfig {
+            socket_path: Some(path.to_string_lossy().into_owned()),
+            ..JournaldConfig::default()
+        };
+
+        let message = "x".repeat(4 * 1024 * 1024);
+        let result = journald_output(&config, &entry(LogLevel::Error.into(), json!(message)));
+        let mut buf = vec![0; 2 * TRUNCATED_DATAGRAM_SIZE];
+        let received = journal.recv(&mut buf);
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(result, Ok(()));
+        let datagram = &buf[..received.unwrap()];
+        let expected = format!("MESSAGE={}\nPRIORITY=3\n", "x".repeat(TRUNCATED_VALUE_SIZE));
+        assert!(datagram.starts_with(expected.as_bytes()));
+    }
+}
@@ -0,0 +1,103 @@
+use std::borrow::Cow;
+
+use serde::{Serialize, Serializer};
+
+use crate::types::{CustomLevel, EnvConfig, LogLevel};
+
+pub const BUILTIN_LEVELS: [LogLevel; 6] = [
+    LogLevel::Trace,
+    LogLevel::Debug,
+    LogLevel::Info,
+    LogLevel::Warn,
+    LogLevel::Error,
+    LogLevel::Fatal,
+];
+
+impl LogLevel {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LogLevel::Trace => "Trace",
+            LogLevel::Debug => "Debug",
+            LogLevel::Info => "Info",
+            LogLevel::Warn => "Warn",
+            LogLevel::Error => "Error",
+            LogLevel::Fatal => "Fatal",
+        }
+    }
+
+    pub fn severity(&self) -> u32 {
+        match self {
+            LogLevel::Trace => 10,
+            LogLevel::Debug => 20,
+            LogLevel::Info => 30,
+            LogLevel::Warn => 40,
+            LogLevel::Error => 50,
+            LogLevel::Fatal => 60,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Level {
+    pub name: Cow<'static, str>,
+    pub severity: u32,
+}
+
+impl From<LogLevel> for Level {
+    fn from(level: LogLevel) -> Self {
+        Level {
+            name: Cow::Borrowed(level.name()),
+            severity: level.severity(),
+        }
+    }
+}
+
+// Names are trimmed, as config validation checks them trimmed.
+impl From<&CustomLevel> for Level {
+    fn from(level: &CustomLevel) -> Self {
+        Level {
+            name: Cow::Owned(level.name.trim().to_string()),
+            severity: level.severity,
+        }
+    }
+}
+
+impl Serialize for Level {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.name)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LevelRegistry {
+    pub custom: Vec<Level>,
+}
+
+impl From<&EnvConfig> for LevelRegistry {
+    fn from(config: &EnvConfig) -> Self {
+        LevelRegistry {
+            custom: config
+                .custom_levels
+                .iter()
+                .flatten()
+                .map(Level::from)
+                .collect(),
+        }
+    }
+}
+
+impl LevelRegistry {
+    // Level names are matched case-insensitively, so "info" and "Info" are the same level.
+    pub fn lookup(&self, name: &str) -> Option<Level> {
+        BUILTIN_LEVELS
+            .iter()
+            .find(|level| level.name().eq_ignore_ascii_case(name))
+            .map(|level| Level::from(*level))
+            .or_else(|| {
+                self.custom
+                    .iter()
+                    .find(|level| level.name.eq_ignore_ascii_case(name))
+                    .cloned()
+            })
+    }
+}
@@ -1,6 +1,26 @@
+pub mod base;
+pub mod caller;
 pub mod config;
+pub mod context;
+pub mod directives;
+pub mod environments;
+pub mod fallback;
+pub mod file;
 pub mod format;
-mod logger;
+pub mod http;
+pub mod journald;
+pub mod levels;
+pub mod loader;
+pub mod logger;
 pub mod masking;
+pub mod otlp;
+pub mod retention;
+pub mod rotation;
+pub mod sampling;
+pub mod stream;
+pub mod syslog;
+pub mod theme;
+pub mod thread;
 pub mod types;
 pub mod utils;
+pub mod validation;
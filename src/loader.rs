@@ -0,0 +1,276 @@
+use napi::Either;
+use serde::de::{self, DeserializeOwned, Deserializer};
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::fallback::target_name;
+use crate::types::{
+    ColorMode, ConfigIssue, LoggerConfig, OutputConfig, OutputFormat, OutputTarget,
+};
+
+// `EVENTUM_OUTPUT_FILE_PATH` overrides `output.filePath`, and so on for every
+// `OutputConfig` field.
+pub const ENV_PREFIX: &str = "EVENTUM_OUTPUT_";
+
+const TARGETS: [OutputTarget; 10] = [
+    OutputTarget::Stdout,
+    OutputTarget::Stderr,
+    OutputTarget::File,
+    OutputTarget::Null,
+    OutputTarget::Syslog,
+    OutputTarget::Http,
+    OutputTarget::Tcp,
+    OutputTarget::UnixSocket,
+    OutputTarget::Journald,
+    OutputTarget::Otlp,
+];
+
+const FORMATS: [(OutputFormat, &str); 3] = [
+    (OutputFormat::Text, "text"),
+    (OutputFormat::Json, "json"),
+    (OutputFormat::Logfmt, "logfmt"),
+];
+
+// Accepts the index used from JavaScript (`2`) or the name (`file`, `unixSocket`,
+// `unix_socket`), ignoring case.
+impl FromStr for OutputTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let found = match s.parse::<usize>() {
+            Ok(index) => TARGETS.get(index).copied(),
+            Err(_) => {
+                let name = s.replace(['_', '-'], "");
+                TARGETS
+                    .iter()
+                    .copied()
+                    .find(|target| target_name(*target).eq_ignore_ascii_case(&name))
+            }
+        };
+        found.ok_or_else(|| format!("'{}' is not an output target", s))
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let found = match s.parse::<usize>() {
+            Ok(index) => FORMATS.get(index),
+            Err(_) => FORMATS
+                .iter()
+                .find(|(_, name)| name.eq_ignore_ascii_case(s)),
+        };
+        found
+            .map(|(format, _)| *format)
+            .ok_or_else(|| format!("'{}' is not an output format", s))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NameOrIndex {
+    Index(u64),
+    Name(String),
+}
+
+fn from_name_or_index<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr<Err = String>,
+{
+    let value = match NameOrIndex::deserialize(deserializer)? {
+        NameOrIndex::Index(index) => index.to_string(),
+        NameOrIndex::Name(name) => name,
+    };
+    value.parse().map_err(de::Error::custom)
+}
+
+impl<'de> Deserialize<'de> for OutputTarget {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        from_name_or_index(deserializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for OutputFormat {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        from_name_or_index(deserializer)
+    }
+}
+
+// Names, so that a serialized config reads like a config file.
+impl Serialize for OutputTarget {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(target_name(*self))
+    }
+}
+
+impl Serialize for OutputFormat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let name = FORMATS
+            .iter()
+            .find(|(format, _)| format == self)
+            .map_or("text", |(_, name)| *name);
+        serializer.serialize_str(name)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ColorSetting {
+    Enabled(bool),
+    Mode(ColorMode),
+}
+
+// `color` is `true`, `false` or a `ColorMode` name, as in JavaScript.
+pub fn color_setting<'de, D>(deserializer: D) -> Result<Option<Either<bool, ColorMode>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(
+        Option::<ColorSetting>::deserialize(deserializer)?.map(|setting| match setting {
+            ColorSetting::Enabled(enabled) => Either::A(enabled),
+            ColorSetting::Mode(mode) => Either::B(mode),
+        }),
+    )
+}
+
+pub fn serialize_color<S: Serializer>(
+    color: &Option<Either<bool, ColorMode>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match color {
+        Some(Either::A(enabled)) => serializer.serialize_bool(*enabled),
+        Some(Either::B(mode)) => mode.serialize(serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+// Reads a config file with the same shape as the `setConfig()` argument. The
+// format is taken from the extension.
+pub fn load_file(path: &str) -> Result<LoggerConfig, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|err| format!("failed to read config file {}: {}", path, err))?;
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+
+    let parsed = match extension.as_deref() {
+        Some("json") => serde_json::from_str(&text).map_err(|err| err.to_string()),
+        Some("toml") => toml::from_str(&text).map_err(|err| err.to_string()),
+        Some("yaml" | "yml") => serde_yaml::from_str(&text).map_err(|err| err.to_string()),
+        _ => {
+            return Err(format!(
+                "config file {} must end in .json, .toml, .yaml or .yml",
+                path
+            ))
+        }
+    };
+    parsed.map_err(|err| format!("failed to parse config file {}: {}", path, err.trim_end()))
+}
+
+fn flag(value: &str) -> Result<bool, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Ok(true),
+        "false" | "0" | "no" | "off" => Ok(false),
+        _ => Err(format!("'{}' is not a boolean", value)),
+    }
+}
+
+fn number<T: FromStr>(value: &str) -> Result<T, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{}' is not a valid number", value))
+}
+
+// Modes are octal, as with chmod: `640` or `0o640`.
+fn mode(value: &str) -> Result<u32, String> {
+    let digits = value.trim().trim_start_matches("0o");
+    u32::from_str_radix(digits, 8).map_err(|_| format!("'{}' is not an octal mode", value))
+}
+
+// String enums, by the same names as in config files.
+fn named<T: DeserializeOwned>(value: &str) -> Result<T, String> {
+    serde_json::from_value(Value::String(value.to_string())).map_err(|err| err.to_string())
+}
+
+// Nested objects such as `http` are given as JSON and replace the whole object.
+fn json<T: DeserializeOwned>(value: &str) -> Result<T, String> {
+    serde_json::from_str(value).map_err(|err| format!("invalid JSON: {}", err))
+}
+
+fn apply_override(output: &mut OutputConfig, key: &str, value: &str) -> Result<(), String> {
+    match key {
+        "COLOR" => {
+            output.color = Some(match flag(value) {
+                Ok(enabled) => Either::A(enabled),
+                Err(_) => Either::B(named(value)?),
+            })
+        }
+        "THEME" => output.theme = Some(json(value)?),
+        "FORMAT" => output.format = value.parse()?,
+        "TARGET" => output.target = value.parse()?,
+        "FILE_PATH" => output.file_path = Some(value.to_string()),
+        "MAX_FILE_SIZE" => output.max_file_size = Some(number(value)?),
+        "MAX_BACKUPS" => output.max_backups = Some(number(value)?),
+        "MAX_AGE_DAYS" => output.max_age_days = Some(number(value)?),
+        "MAX_TOTAL_SIZE" => output.max_total_size = Some(number(value)?),
+        "ROTATE_DAILY" => output.rotate_daily = Some(flag(value)?),
+        "ROTATE_HOURLY" => output.rotate_hourly = Some(flag(value)?),
+        "ROTATE_WEEKLY" => output.rotate_weekly = Some(flag(value)?),
+        "ROTATE_EVERY_MINUTES" => output.rotate_every_minutes = Some(number(value)?),
+        "ROTATION_TIMEZONE" => output.rotation_timezone = Some(named(value)?),
+        "FILE_PATTERN" => output.file_pattern = Some(value.to_string()),
+        "REOPEN_SIGNAL" => output.reopen_signal = Some(flag(value)?),
+        "FILE_MODE" => output.file_mode = Some(mode(value)?),
+        "DIR_MODE" => output.dir_mode = Some(mode(value)?),
+        "CURRENT_LINK" => output.current_link = Some(flag(value)?),
+        "FSYNC" => output.fsync = Some(named(value)?),
+        "FSYNC_INTERVAL_MS" => output.fsync_interval_ms = Some(number(value)?),
+        "FSYNC_LEVEL" => output.fsync_level = Some(value.to_string()),
+        "BATCH_ENABLED" => output.batch_enabled = Some(flag(value)?),
+        "BATCH_SIZE" => output.batch_size = Some(number(value)?),
+        "BATCH_INTERVAL_MS" => output.batch_interval_ms = Some(number(value)?),
+        "MASKING" => output.masking = Some(json(value)?),
+        "SYSLOG" => output.syslog = Some(json(value)?),
+        "HTTP" => output.http = Some(json(value)?),
+        "STREAM" => output.stream = Some(json(value)?),
+        "JOURNALD" => output.journald = Some(json(value)?),
+        "OTLP" => output.otlp = Some(json(value)?),
+        "FALLBACK" => {
+            output.fallback = Some(
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|target| !target.is_empty())
+                    .map(str::parse)
+                    .collect::<Result<_, _>>()?,
+            )
+        }
+        "FALLBACK_COOLDOWN_MS" => output.fallback_cooldown_ms = Some(number(value)?),
+        _ => return Err("not an OutputConfig field".to_string()),
+    }
+    Ok(())
+}
+
+// Environment variables take precedence over the config they are applied to.
+// Each invalid variable is reported, by name.
+pub fn apply_env_overrides(output: &mut OutputConfig) -> Vec<ConfigIssue> {
+    let mut overrides: Vec<(String, String)> = std::env::vars()
+        .filter_map(|(key, value)| Some((key.strip_prefix(ENV_PREFIX)?.to_string(), value)))
+        .collect();
+    overrides.sort();
+
+    overrides
+        .into_iter()
+        .filter_map(|(key, value)| {
+            let err = apply_override(output, &key, &value).err()?;
+            Some(ConfigIssue::new(format!("{}{}", ENV_PREFIX, key), err))
+        })
+        .collect()
+}
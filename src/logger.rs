@@ -1,67 +1,135 @@
 use chrono::Utc;
+use napi::threadsafe_function::{ErrorStrategy, ThreadSafeCallContext, ThreadsafeFunction};
+use napi::{Either, Env, JsFunction, JsObject};
 use napi_derive::napi;
-use serde_json::Value;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
 use std::option::Option;
-use std::sync::RwLock;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
 
-use crate::config::{BATCH_THREAD, LOGGER_CONFIG, MASKING_RULES, SENDER};
-use crate::format::{format_log_json, format_log_text};
+use crate::base::BaseFields;
+use crate::caller::{capture_caller, register_wrapper};
+use crate::config::{
+    BASE_FIELDS, BATCH_THREAD, CALLER_ENABLED, LEVELS, LEVEL_FILTER, LOGGER_CONFIG, MASKING_RULES,
+    ON_ROTATE, PALETTE, SAMPLER, SAMPLING_ENABLED, SENDER,
+};
+use crate::context::context_fields;
+use crate::directives::{self, level_filter, parse_level};
+use crate::fallback::failure_counts;
+use crate::file;
+use crate::levels::{Level, LevelRegistry};
+use crate::loader::load_file;
 use crate::masking::MaskRule;
+use crate::retention;
+use crate::sampling::Sampler;
+use crate::theme::{color_enabled, Palette};
+use crate::thread::{
+    current_thread_id, current_thread_name, set_current_thread_id, set_current_thread_name,
+};
 use crate::types::{
-    EnvConfig, FieldsConfig, LogEntry, LogLevel, LoggerConfig, OutputFormat, WorkerMsg,
+    CallerLocation, EnvConfig, FieldsConfig, LogEntry, LogLevel, LoggerConfig, RotateEvent,
+    ValidationResult, WorkerMsg,
 };
-use crate::utils::{init_batching_logger, validate_config};
+use crate::utils::{flush_batch, init_batching_logger, scope_of, write_entries};
+use crate::validation;
 
 static WARNED_NO_CONFIG: AtomicBool = AtomicBool::new(false);
 
+// Throws a `ConfigValidationError` listing every problem when the config is invalid.
+#[napi]
+pub fn set_config(env: Env, config: LoggerConfig) -> napi::Result<EnvConfig> {
+    apply_config(env, config)
+}
+
+// Reads a JSON, TOML or YAML file shaped like the `setConfig()` argument.
+// Unreadable files throw with the parser's message, invalid configs as with
+// `setConfig()`.
+#[napi]
+pub fn load_config(env: Env, path: String) -> napi::Result<EnvConfig> {
+    let config = load_file(&path).map_err(|e| napi::Error::new(napi::Status::InvalidArg, e))?;
+    apply_config(env, config)
+}
+
+// Checks a config without applying it, e.g. in CI.
 #[napi]
-pub fn set_config(config: LoggerConfig) -> Option<EnvConfig> {
-    let selected_config = match std::env::var("NODE_ENV").as_deref() {
-        Ok("production") => config.prod.clone(),
-        _ => config.dev.clone().or(config.prod.clone()),
+pub fn validate_config(config: LoggerConfig) -> ValidationResult {
+    validation::check(config).1
+}
+
+// Precedence, lowest first: built-in defaults, the selected section of the
+// config, then EVENTUM_OUTPUT_* variables (and EVENTUM_LOG for levels).
+fn apply_config(env: Env, config: LoggerConfig) -> napi::Result<EnvConfig> {
+    let (env_config, result) = validation::check(config);
+    let Some(mut env_config) = env_config else {
+        return Err(validation::invalid_config(env, result));
     };
+    for warning in &result.warnings {
+        eprintln!("[Logger] Config warning: {}", warning);
+    }
 
-    if let Some(mut env_config) = selected_config {
-        if let Err(e) = validate_config(&env_config) {
-            eprintln!("[Logger] Invalid config: {}", e);
-            return None;
-        }
+    if env_config.fields.is_none() {
+        env_config.fields = Some(FieldsConfig::default());
+    }
 
-        if env_config.fields.is_none() {
-            env_config.fields = Some(FieldsConfig::default());
-        }
+    // Report what the previous config suppressed before its outputs are replaced.
+    flush_sampler();
 
-        let cell = LOGGER_CONFIG.get_or_init(|| RwLock::new(env_config.clone()));
-        let mut current = cell.write().expect("Logger config lock poisoned");
-        *current = env_config.clone();
+    let cell = LOGGER_CONFIG.get_or_init(|| RwLock::new(env_config.clone()));
+    let mut current = cell.write().expect("Logger config lock poisoned");
+    *current = env_config.clone();
 
-        if env_config.output.masking.is_some() {
-            if let Some(masking_cfg) = &env_config.output.masking {
-                let rules = MaskRule::from(masking_cfg.clone());
-                let cell = MASKING_RULES.get_or_init(|| RwLock::new(rules.clone()));
-                *cell.write().expect("Masking rules lock poisoned") = rules;
-            }
+    if env_config.output.masking.is_some() {
+        if let Some(masking_cfg) = &env_config.output.masking {
+            let rules = MaskRule::from(masking_cfg.clone());
+            let cell = MASKING_RULES.get_or_init(|| RwLock::new(rules.clone()));
+            *cell.write().expect("Masking rules lock poisoned") = rules;
         }
+    }
 
-        let needs_init = if let Some(sender_mutex) = SENDER.get() {
-            sender_mutex.lock().expect("Sender mutex poisoned").is_none()
-        } else {
-            true
-        };
+    let caller_enabled = env_config
+        .fields
+        .as_ref()
+        .and_then(|fields| fields.caller)
+        .unwrap_or(false);
+    CALLER_ENABLED.store(caller_enabled, Ordering::Relaxed);
 
-        if needs_init {
-            init_batching_logger(&env_config);
-        }
+    let levels = LevelRegistry::from(&env_config);
+    directives::install(level_filter(&env_config, &levels));
 
-        Some(env_config)
-    } else {
-        eprintln!("[Logger] No valid logger config for current NODE_ENV. Logger disabled.");
-        None
-    }
+    let sampler = env_config
+        .sampling
+        .as_ref()
+        .map(|sampling| Sampler::new(sampling, &levels))
+        .unwrap_or_default();
+    SAMPLING_ENABLED.store(sampler.is_active(), Ordering::Relaxed);
+    SAMPLER.store(Arc::new(sampler));
+    let cell = LEVELS.get_or_init(|| RwLock::new(levels.clone()));
+    *cell.write().expect("Levels lock poisoned") = levels;
+
+    let palette = color_enabled(&env_config.output).then(|| {
+        Palette::new(
+            &env_config.output.theme.clone().unwrap_or_default(),
+            env_config.custom_levels.as_deref().unwrap_or_default(),
+        )
+    });
+    colored::control::set_override(palette.is_some());
+    let cell = PALETTE.get_or_init(|| RwLock::new(palette.clone()));
+    *cell.write().expect("Palette lock poisoned") = palette;
+
+    let base_fields = BaseFields::from(&env_config);
+    let cell = BASE_FIELDS.get_or_init(|| RwLock::new(base_fields.clone()));
+    *cell.write().expect("Base fields lock poisoned") = base_fields;
+
+    init_batching_logger(&env_config);
+
+    Ok(env_config)
 }
 
-fn log(entry: LogEntry) {
+// Context fields, as a function returning them or as an object.
+type Context = Either<JsFunction, JsObject>;
+
+fn log(env: &Env, level: Level, message: Value, context: Option<Context>) {
     if LOGGER_CONFIG.get().is_none() {
         if !WARNED_NO_CONFIG.swap(true, Ordering::Relaxed) {
             eprintln!("[Eventum] Logger used before setConfig(). Logs are discarded.");
@@ -69,6 +137,82 @@ fn log(entry: LogEntry) {
         return;
     }
 
+    if !LEVEL_FILTER.load().enabled(&level, || scope_of(&message)) {
+        return;
+    }
+
+    let mut summaries = Vec::new();
+    let admitted =
+        !SAMPLING_ENABLED.load(Ordering::Relaxed) || admit(&level, &message, &mut summaries);
+    emit_summaries(summaries);
+    if !admitted {
+        return;
+    }
+
+    let caller = if CALLER_ENABLED.load(Ordering::Relaxed) {
+        capture_caller(env)
+    } else {
+        None
+    };
+    let context = context.and_then(|context| resolve_context(env, context));
+
+    emit(new_entry(level, message, caller, context));
+}
+
+// The JS wrappers pass a function that reads and sanitizes the async context,
+// so entries dropped by level or sampling never pay for it.
+fn resolve_context(env: &Env, context: Context) -> Option<Map<String, Value>> {
+    let fields = match context {
+        Either::A(read) => {
+            let value = read.call_without_args(None).ok()?;
+            env.from_js_value::<Option<Map<String, Value>>, _>(value)
+                .ok()??
+        }
+        Either::B(fields) => env.from_js_value(fields).ok()?,
+    };
+
+    let base = BASE_FIELDS
+        .get()
+        .map(|cell| cell.read().expect("Base fields lock poisoned"));
+    let masking = MASKING_RULES
+        .get()
+        .map(|cell| cell.read().expect("Masking rules lock poisoned"));
+    context_fields(
+        fields,
+        base.as_deref().unwrap_or(&BaseFields::default()),
+        masking.as_deref(),
+    )
+}
+
+fn admit(level: &Level, message: &Value, summaries: &mut Vec<(Level, Value)>) -> bool {
+    SAMPLER.load().admit(level, message, summaries)
+}
+
+fn emit_summaries(summaries: Vec<(Level, Value)>) {
+    for (level, message) in summaries {
+        emit(new_entry(level, message, None, None));
+    }
+}
+
+fn flush_sampler() {
+    emit_summaries(SAMPLER.load().drain());
+}
+
+// Summaries that became due while nothing was logged, written by the batch
+// thread's timer.
+pub fn sampler_summaries() -> Vec<LogEntry> {
+    if !SAMPLING_ENABLED.load(Ordering::Relaxed) {
+        return Vec::new();
+    }
+    SAMPLER
+        .load()
+        .tick()
+        .into_iter()
+        .map(|(level, message)| new_entry(level, message, None, None))
+        .collect()
+}
+
+fn emit(entry: LogEntry) {
     if let Some(sender_mutex) = SENDER.get() {
         if let Some(sender) = sender_mutex.lock().expect("Sender mutex poisoned").as_ref() {
             let _ = sender.send(WorkerMsg::Entry(entry));
@@ -81,87 +225,197 @@ fn log(entry: LogEntry) {
     };
     let config = config_cell.read().expect("Logger config lock poisoned");
 
-    match config.output.format {
-        OutputFormat::Text => format_log_text(&entry, &config),
-        OutputFormat::Json => format_log_json(&entry, &config),
-    }
+    write_entries(&config, std::slice::from_ref(&entry));
 }
 
-#[napi]
-pub fn trace(message: Value) {
-    log(LogEntry {
-        level: LogLevel::Trace,
+fn new_entry(
+    level: Level,
+    message: Value,
+    caller: Option<CallerLocation>,
+    context: Option<Map<String, Value>>,
+) -> LogEntry {
+    LogEntry {
+        level,
         time: Utc::now().timestamp_millis(),
         pid: std::process::id(),
+        thread_id: current_thread_id(),
+        thread_name: current_thread_name(),
+        caller,
+        context,
         msg: message,
-    });
+    }
 }
 
 #[napi]
-pub fn info(message: Value) {
-    log(LogEntry {
-        level: LogLevel::Info,
-        time: Utc::now().timestamp_millis(),
-        pid: std::process::id(),
-        msg: message,
-    });
+pub fn trace(env: Env, message: Value, context: Option<Context>) {
+    log(&env, LogLevel::Trace.into(), message, context);
 }
 
 #[napi]
-pub fn debug(message: Value) {
-    log(LogEntry {
-        level: LogLevel::Debug,
-        time: Utc::now().timestamp_millis(),
-        pid: std::process::id(),
-        msg: message,
-    });
+pub fn info(env: Env, message: Value, context: Option<Context>) {
+    log(&env, LogLevel::Info.into(), message, context);
 }
 
 #[napi]
-pub fn warn(message: Value) {
-    log(LogEntry {
-        level: LogLevel::Warn,
-        time: Utc::now().timestamp_millis(),
-        pid: std::process::id(),
-        msg: message,
-    });
+pub fn debug(env: Env, message: Value, context: Option<Context>) {
+    log(&env, LogLevel::Debug.into(), message, context);
 }
 
 #[napi]
-pub fn error(message: Value) {
-    log(LogEntry {
-        level: LogLevel::Error,
-        time: Utc::now().timestamp_millis(),
-        pid: std::process::id(),
-        msg: message,
-    });
+pub fn warn(env: Env, message: Value, context: Option<Context>) {
+    log(&env, LogLevel::Warn.into(), message, context);
 }
 
 #[napi]
-pub fn fatal(message: Value) {
-    log(LogEntry {
-        level: LogLevel::Fatal,
-        time: Utc::now().timestamp_millis(),
-        pid: std::process::id(),
-        msg: message,
-    });
+pub fn error(env: Env, message: Value, context: Option<Context>) {
+    log(&env, LogLevel::Error.into(), message, context);
 }
 
 #[napi]
-pub fn shutdown() {
-    if let Some(sender_mutex) = SENDER.get() {
-        if let Some(sender) = sender_mutex.lock().expect("Sender mutex poisoned").as_ref() {
-            let _ = sender.send(WorkerMsg::Shutdown);
-        }
-    }
+pub fn fatal(env: Env, message: Value, context: Option<Context>) {
+    log(&env, LogLevel::Fatal.into(), message, context);
+}
+
+#[napi(js_name = "log")]
+pub fn log_level(
+    env: Env,
+    level: String,
+    message: Value,
+    context: Option<Context>,
+) -> napi::Result<()> {
+    let level = LEVELS
+        .get()
+        .and_then(|levels| levels.read().expect("Levels lock poisoned").lookup(&level))
+        .or_else(|| LevelRegistry::default().lookup(&level))
+        .ok_or_else(|| {
+            napi::Error::new(
+                napi::Status::InvalidArg,
+                format!("Unknown log level '{}'", level),
+            )
+        })?;
+
+    log(&env, level, message, context);
+    Ok(())
+}
+
+#[napi]
+pub fn set_level(level: String, scope: Option<String>) -> napi::Result<()> {
+    let registry = LEVELS
+        .get()
+        .map(|levels| levels.read().expect("Levels lock poisoned").clone())
+        .unwrap_or_default();
+    let level = parse_level(&level, &registry)
+        .map_err(|e| napi::Error::new(napi::Status::InvalidArg, e))?;
+
+    directives::update(level, scope.as_deref());
+    Ok(())
+}
+
+#[napi]
+pub fn get_level(scope: Option<String>) -> Option<String> {
+    LEVEL_FILTER
+        .load()
+        .filter
+        .level_for(scope.as_deref())
+        .map(|level| level.name.to_string())
+}
+
+#[napi]
+pub fn toggle_debug_level() -> bool {
+    directives::toggle_debug()
+}
+
+// Closes the log file so the next entry opens `filePath` again, e.g. after
+// logrotate moved it away.
+#[napi]
+pub fn reopen() {
+    file::reopen();
+}
+
+// Starts a new log file now, after writing queued entries to the old one.
+// Returns false when nothing was written yet.
+#[napi]
+pub fn rotate() -> napi::Result<bool> {
+    let Some(cfg_cell) = LOGGER_CONFIG.get() else {
+        return Ok(false);
+    };
+    flush_batch();
+    let output = cfg_cell
+        .read()
+        .expect("Logger config lock poisoned")
+        .output
+        .clone();
+    file::rotate(&output).map_err(|e| napi::Error::new(napi::Status::GenericFailure, e))
+}
 
-    if let Some(thread_mutex) = BATCH_THREAD.get() {
-        if let Some(handle) = thread_mutex.lock().expect("Batch thread mutex poisoned").take() {
-            let _ = handle.join();
+// Replaces the rotation callback; `null` removes it. The callback does not keep
+// the process alive.
+#[napi(ts_args_type = "callback: ((event: RotateEvent) => void) | null")]
+pub fn on_rotate(env: Env, callback: Option<JsFunction>) -> napi::Result<()> {
+    let callback = match callback {
+        Some(callback) => {
+            let mut tsfn: ThreadsafeFunction<RotateEvent, ErrorStrategy::Fatal> = callback
+                .create_threadsafe_function(0, |ctx: ThreadSafeCallContext<RotateEvent>| {
+                    Ok(vec![ctx.value])
+                })?;
+            tsfn.unref(&env)?;
+            Some(tsfn)
         }
+        None => None,
+    };
+    *ON_ROTATE.lock().expect("Rotate callback mutex poisoned") = callback;
+    Ok(())
+}
+
+#[napi]
+pub fn get_sink_failures() -> HashMap<String, u32> {
+    failure_counts()
+}
+
+// Called by the wrappers as they load, in every thread.
+#[napi]
+pub fn register_thread(env: Env, thread_id: u32) {
+    set_current_thread_id(thread_id);
+    register_wrapper(&env);
+}
+
+#[napi]
+pub fn set_thread_name(name: Option<String>) {
+    set_current_thread_name(name);
+}
+
+#[napi]
+pub fn shutdown() {
+    flush_sampler();
+    stop_batch_thread();
+    file::sync();
+    retention::flush();
+}
+
+fn stop_batch_thread() {
+    let Some(sender_mutex) = SENDER.get() else {
+        return;
+    };
+
+    // Take the sender and the worker handle under the same lock so that other
+    // worker threads fall back to synchronous writes instead of queueing into a
+    // dying worker, and a concurrent setConfig() cannot swap the handle we join.
+    let (sender, handle) = {
+        let mut sender_slot = sender_mutex.lock().expect("Sender mutex poisoned");
+        let handle = BATCH_THREAD.get().and_then(|thread_mutex| {
+            thread_mutex
+                .lock()
+                .expect("Batch thread mutex poisoned")
+                .take()
+        });
+        (sender_slot.take(), handle)
+    };
+
+    if let Some(sender) = sender {
+        let _ = sender.send(WorkerMsg::Shutdown);
     }
 
-    if let Some(sender_mutex) = SENDER.get() {
-        *sender_mutex.lock().expect("Sender mutex poisoned") = None;
+    if let Some(handle) = handle {
+        let _ = handle.join();
     }
 }
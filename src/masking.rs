@@ -3,7 +3,7 @@ use serde_json::{Map, Value};
 
 use crate::types::MaskingConfig;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct MaskRule {
     pub exact: Vec<String>,
     pub partial: Vec<String>,
@@ -31,12 +31,7 @@ impl From<MaskingConfig> for MaskRule {
 
 impl MaskRule {
     pub fn new() -> Self {
-        MaskRule {
-            exact: Vec::new(),
-            partial: Vec::new(),
-            regex: Vec::new(),
-            keyword: String::new(),
-        }
+        Self::default()
     }
 
     pub fn mask_value(&self, key: &str, value: &str) -> String {
@@ -0,0 +1,349 @@
+use once_cell::sync::Lazy;
+use serde_json::{json, Value};
+
+use crate::context::hex_id;
+use crate::http::{compress_body, post_batch};
+use crate::levels::Level;
+use crate::types::{HttpConfig, LogEntry, OtlpConfig, OtlpEncoding};
+use crate::utils::{extract_scope_and_value, mask_message_if_needed, with_base_fields};
+
+const DEFAULT_ENDPOINT: &str = "http://localhost:4318/v1/logs";
+const SCOPE_NAME: &str = "eventum";
+const SCOPE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+static HOSTNAME: Lazy<String> =
+    Lazy::new(|| gethostname::gethostname().to_string_lossy().into_owned());
+
+// OTLP SeverityNumber: each range of Eventum severities lands on the first
+// value of the matching OTLP band (TRACE = 1, DEBUG = 5, ... FATAL = 21).
+pub fn severity_number(level: &Level) -> u32 {
+    match level.severity {
+        0..=19 => 1,
+        20..=29 => 5,
+        30..=39 => 9,
+        40..=49 => 13,
+        50..=59 => 17,
+        _ => 21,
+    }
+}
+
+pub struct LogRecord {
+    pub time_unix_nano: u64,
+    pub severity_number: u32,
+    pub severity_text: String,
+    pub body: Value,
+    pub attributes: Vec<(String, Value)>,
+    pub trace_id: Option<Vec<u8>>,
+    pub span_id: Option<Vec<u8>>,
+}
+
+impl From<&LogEntry> for LogRecord {
+    fn from(entry: &LogEntry) -> Self {
+        let masked_msg = mask_message_if_needed(&entry.msg);
+        let (scope, body) = extract_scope_and_value(&masked_msg);
+
+        // Valid W3C ids from the context populate the record's trace fields;
+        // every other context key is carried as an attribute.
+        let mut trace_id = None;
+        let mut span_id = None;
+        let mut attributes = Vec::new();
+        for (key, value) in entry.context.iter().flatten() {
+            let decoded = match key.as_str() {
+                "traceId" => hex_id(value, 16).map(|id| trace_id = Some(id)),
+                "spanId" => hex_id(value, 8).map(|id| span_id = Some(id)),
+                _ => None,
+            };
+            if decoded.is_none() {
+                attributes.push((key.clone(), value.clone()));
+            }
+        }
+        if let Some(scope) = scope {
+            attributes.push(("scope".to_string(), Value::from(scope)));
+        }
+        attributes.push(("thread.id".to_string(), Value::from(entry.thread_id)));
+        if let Some(name) = &entry.thread_name {
+            attributes.push(("thread.name".to_string(), Value::from(name.clone())));
+        }
+        if let Some(caller) = &entry.caller {
+            attributes.push((
+                "code.file.path".to_string(),
+                Value::from(caller.file.clone()),
+            ));
+            attributes.push(("code.line.number".to_string(), Value::from(caller.line)));
+            if let Some(function) = &caller.function {
+                attributes.push((
+                    "code.function.name".to_string(),
+                    Value::from(function.clone()),
+                ));
+            }
+        }
+
+        LogRecord {
+            time_unix_nano: (entry.time.max(0) as u64).saturating_mul(1_000_000),
+            severity_number: severity_number(&entry.level),
+            severity_text: entry.level.name.to_string(),
+            body,
+            attributes,
+            trace_id,
+            span_id,
+        }
+    }
+}
+
+pub fn resource_attributes(config: &OtlpConfig) -> Vec<(String, Value)> {
+    let mut attributes = with_base_fields(|base| {
+        let service = base.service.as_deref().unwrap_or("unknown_service:node");
+
+        let mut attributes = vec![
+            ("service.name".to_string(), Value::from(service)),
+            ("host.name".to_string(), Value::from(HOSTNAME.as_str())),
+            ("process.pid".to_string(), Value::from(std::process::id())),
+        ];
+        for (key, value) in [
+            ("service.version", &base.version),
+            ("deployment.environment.name", &base.env),
+            ("cloud.region", &base.region),
+        ] {
+            if let Some(value) = value {
+                attributes.push((key.to_string(), Value::from(value.as_str())));
+            }
+        }
+        attributes
+    });
+
+    let mut custom: Vec<_> = config.resource_attributes.iter().flatten().collect();
+    custom.sort();
+    for (key, value) in custom {
+        attributes.retain(|(existing, _)| existing != key);
+        attributes.push((key.clone(), Value::from(value.clone())));
+    }
+
+    attributes
+}
+
+// Minimal protobuf writer for the handful of OTLP messages we emit.
+mod proto {
+    use serde_json::Value;
+
+    const VARINT: u8 = 0;
+    const FIXED64: u8 = 1;
+    const LEN: u8 = 2;
+
+    fn varint(buf: &mut Vec<u8>, mut value: u64) {
+        while value >= 0x80 {
+            buf.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        buf.push(value as u8);
+    }
+
+    fn tag(buf: &mut Vec<u8>, field: u32, wire: u8) {
+        varint(buf, ((field as u64) << 3) | wire as u64);
+    }
+
+    pub fn uint(buf: &mut Vec<u8>, field: u32, value: u64) {
+        tag(buf, field, VARINT);
+        varint(buf, value);
+    }
+
+    pub fn fixed64(buf: &mut Vec<u8>, field: u32, value: u64) {
+        tag(buf, field, FIXED64);
+        buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn bytes(buf: &mut Vec<u8>, field: u32, value: &[u8]) {
+        tag(buf, field, LEN);
+        varint(buf, value.len() as u64);
+        buf.extend_from_slice(value);
+    }
+
+    // AnyValue: string = 1, bool = 2, int = 3, double = 4, array = 5, kvlist = 6.
+    pub fn any_value(value: &Value) -> Vec<u8> {
+        let mut buf = Vec::new();
+        match value {
+            Value::Null => {}
+            Value::String(s) => bytes(&mut buf, 1, s.as_bytes()),
+            Value::Bool(b) => uint(&mut buf, 2, *b as u64),
+            Value::Number(n) => match n.as_i64() {
+                Some(i) => uint(&mut buf, 3, i as u64),
+                None => {
+                    tag(&mut buf, 4, FIXED64);
+                    buf.extend_from_slice(&n.as_f64().unwrap_or(0.0).to_le_bytes());
+                }
+            },
+            Value::Array(items) => {
+                let mut array = Vec::new();
+                for item in items {
+                    bytes(&mut array, 1, &any_value(item));
+                }
+                bytes(&mut buf, 5, &array);
+            }
+            Value::Object(map) => {
+                let mut kvlist = Vec::new();
+                for (key, value) in map {
+                    bytes(&mut kvlist, 1, &key_value(key, value));
+                }
+                bytes(&mut buf, 6, &kvlist);
+            }
+        }
+        buf
+    }
+
+    pub fn key_value(key: &str, value: &Value) -> Vec<u8> {
+        let mut buf = Vec::new();
+        bytes(&mut buf, 1, key.as_bytes());
+        bytes(&mut buf, 2, &any_value(value));
+        buf
+    }
+}
+
+pub fn encode_protobuf(resource: &[(String, Value)], records: &[LogRecord]) -> Vec<u8> {
+    let mut resource_msg = Vec::new();
+    for (key, value) in resource {
+        proto::bytes(&mut resource_msg, 1, &proto::key_value(key, value));
+    }
+
+    let mut scope_msg = Vec::new();
+    proto::bytes(&mut scope_msg, 1, SCOPE_NAME.as_bytes());
+    proto::bytes(&mut scope_msg, 2, SCOPE_VERSION.as_bytes());
+
+    let mut scope_logs = Vec::new();
+    proto::bytes(&mut scope_logs, 1, &scope_msg);
+    for record in records {
+        let mut log = Vec::new();
+        proto::fixed64(&mut log, 1, record.time_unix_nano);
+        proto::uint(&mut log, 2, record.severity_number as u64);
+        proto::bytes(&mut log, 3, record.severity_text.as_bytes());
+        proto::bytes(&mut log, 5, &proto::any_value(&record.body));
+        for (key, value) in &record.attributes {
+            proto::bytes(&mut log, 6, &proto::key_value(key, value));
+        }
+        if let Some(trace_id) = &record.trace_id {
+            proto::bytes(&mut log, 9, trace_id);
+        }
+        if let Some(span_id) = &record.span_id {
+            proto::bytes(&mut log, 10, span_id);
+        }
+        proto::fixed64(&mut log, 11, record.time_unix_nano);
+        proto::bytes(&mut scope_logs, 2, &log);
+    }
+
+    let mut resource_logs = Vec::new();
+    proto::bytes(&mut resource_logs, 1, &resource_msg);
+    proto::bytes(&mut resource_logs, 2, &scope_logs);
+
+    let mut request = Vec::new();
+    proto::bytes(&mut request, 1, &resource_logs);
+    request
+}
+
+// OTLP/JSON AnyValue. 64-bit integers are encoded as strings, per the protobuf JSON mapping.
+fn json_any_value(value: &Value) -> Value {
+    match value {
+        Value::Null => json!({}),
+        Value::String(s) => json!({ "stringValue": s }),
+        Value::Bool(b) => json!({ "boolValue": b }),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => json!({ "intValue": i.to_string() }),
+            None => json!({ "doubleValue": n.as_f64() }),
+        },
+        Value::Array(items) => {
+            json!({ "arrayValue": { "values": items.iter().map(json_any_value).collect::<Vec<_>>() } })
+        }
+        Value::Object(map) => json!({
+            "kvlistValue": {
+                "values": map
+                    .iter()
+                    .map(|(key, value)| json_key_value(key, value))
+                    .collect::<Vec<_>>()
+            }
+        }),
+    }
+}
+
+fn json_key_value(key: &str, value: &Value) -> Value {
+    json!({ "key": key, "value": json_any_value(value) })
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn json_attributes(attributes: &[(String, Value)]) -> Vec<Value> {
+    attributes
+        .iter()
+        .map(|(key, value)| json_key_value(key, value))
+        .collect()
+}
+
+pub fn encode_json(resource: &[(String, Value)], records: &[LogRecord]) -> Vec<u8> {
+    let log_records: Vec<Value> = records
+        .iter()
+        .map(|record| {
+            let mut log = json!({
+                "timeUnixNano": record.time_unix_nano.to_string(),
+                "observedTimeUnixNano": record.time_unix_nano.to_string(),
+                "severityNumber": record.severity_number,
+                "severityText": record.severity_text,
+                "body": json_any_value(&record.body),
+                "attributes": json_attributes(&record.attributes),
+            });
+            // The OTLP/JSON mapping encodes trace and span ids as lowercase hex.
+            for (key, id) in [("traceId", &record.trace_id), ("spanId", &record.span_id)] {
+                if let Some(id) = id {
+                    log[key] = Value::from(hex(id));
+                }
+            }
+            log
+        })
+        .collect();
+
+    let request = json!({
+        "resourceLogs": [{
+            "resource": { "attributes": json_attributes(resource) },
+            "scopeLogs": [{
+                "scope": { "name": SCOPE_NAME, "version": SCOPE_VERSION },
+                "logRecords": log_records,
+            }],
+        }],
+    });
+
+    request.to_string().into_bytes()
+}
+
+pub fn otlp_output(config: &OtlpConfig, entries: &[LogEntry], queue: bool) -> Result<(), String> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    let records: Vec<LogRecord> = entries.iter().map(LogRecord::from).collect();
+    let resource = resource_attributes(config);
+
+    let (content_type, body) = match config.encoding.unwrap_or(OtlpEncoding::Protobuf) {
+        OtlpEncoding::Protobuf => (
+            "application/x-protobuf",
+            encode_protobuf(&resource, &records),
+        ),
+        OtlpEncoding::Json => ("application/json", encode_json(&resource, &records)),
+    };
+
+    let http = HttpConfig {
+        url: config
+            .endpoint
+            .clone()
+            .unwrap_or_else(|| DEFAULT_ENDPOINT.to_string()),
+        headers: config.headers.clone(),
+        gzip: config.gzip,
+        timeout_ms: config.timeout_ms,
+        max_retries: config.max_retries,
+        ..HttpConfig::default()
+    };
+
+    post_batch(
+        &http,
+        content_type,
+        compress_body(&http, body),
+        entries.len(),
+        queue,
+    )
+}
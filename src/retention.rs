@@ -0,0 +1,139 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender, SyncSender};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::types::OutputConfig;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug, Default)]
+pub struct Policy {
+    pub max_files: Option<usize>,
+    pub max_age: Option<Duration>,
+    pub max_total_size: Option<u64>,
+}
+
+impl Policy {
+    // `max_files` counts backups only; callers that count the live file subtract it first.
+    pub fn new(output: &OutputConfig, max_files: usize) -> Self {
+        Policy {
+            max_files: Some(max_files),
+            max_age: output
+                .max_age_days
+                .map(|days| Duration::from_secs(u64::from(days) * SECONDS_PER_DAY)),
+            max_total_size: output.max_total_size.map(|size| size.max(0) as u64),
+        }
+    }
+}
+
+pub struct Sweep {
+    pub dir: PathBuf,
+    // Matches backup file names, plain or compressed.
+    pub backups: Regex,
+    // The live file: never deleted, but counted towards `max_total_size`.
+    pub current: PathBuf,
+    pub policy: Policy,
+}
+
+enum Job {
+    Sweep(Sweep),
+    Flush(SyncSender<()>),
+}
+
+static WORKER: Lazy<Mutex<Option<Sender<Job>>>> = Lazy::new(|| Mutex::new(None));
+
+fn run(sweep: Sweep) {
+    let Ok(entries) = fs::read_dir(&sweep.dir) else {
+        return;
+    };
+
+    let mut files: Vec<(SystemTime, u64, PathBuf)> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let name = entry.file_name().into_string().ok()?;
+            if !sweep.backups.is_match(&name) || entry.path() == sweep.current {
+                return None;
+            }
+            let metadata = entry.metadata().ok()?;
+            Some((metadata.modified().ok()?, metadata.len(), entry.path()))
+        })
+        .collect();
+
+    // Newest first: the most recent backups are the ones worth keeping.
+    files.sort_by(|a, b| b.cmp(a));
+
+    let now = SystemTime::now();
+    let mut total = fs::metadata(&sweep.current).map_or(0, |m| m.len());
+    let mut kept = 0;
+    for (modified, size, path) in files {
+        let too_many = sweep.policy.max_files.is_some_and(|max| kept >= max);
+        let too_old = sweep
+            .policy
+            .max_age
+            .is_some_and(|max_age| now.duration_since(modified).is_ok_and(|age| age > max_age));
+        let too_big = sweep
+            .policy
+            .max_total_size
+            .is_some_and(|max| total + size > max);
+
+        if too_many || too_old || too_big {
+            let _ = fs::remove_file(path);
+        } else {
+            kept += 1;
+            total += size;
+        }
+    }
+}
+
+fn worker() -> Sender<Job> {
+    let (tx, rx) = mpsc::channel::<Job>();
+    thread::spawn(move || {
+        for job in rx {
+            match job {
+                Job::Sweep(sweep) => run(sweep),
+                Job::Flush(done) => {
+                    let _ = done.send(());
+                }
+            }
+        }
+    });
+    tx
+}
+
+fn send(job: Job) -> bool {
+    let mut slot = WORKER.lock().expect("Retention worker mutex poisoned");
+    slot.get_or_insert_with(worker).send(job).is_ok()
+}
+
+// Deletions run on a background thread so that rotating never blocks a write.
+pub fn schedule(sweep: Sweep) {
+    send(Job::Sweep(sweep));
+}
+
+// Waits for scheduled sweeps to finish, e.g. before the process exits.
+pub fn flush() {
+    if WORKER
+        .lock()
+        .expect("Retention worker mutex poisoned")
+        .is_none()
+    {
+        return;
+    }
+
+    let (done_tx, done_rx) = mpsc::sync_channel(1);
+    if send(Job::Flush(done_tx)) {
+        let _ = done_rx.recv_timeout(FLUSH_TIMEOUT);
+    }
+}
+
+// `app.log.1`, `app.log.2.gz`, ... next to a size-rotated `app.log`.
+pub fn size_backups(path: &Path) -> Option<Regex> {
+    let name = path.file_name()?.to_str()?;
+    Regex::new(&format!(r"^{}\.\d+(\.gz)?$", regex::escape(name))).ok()
+}
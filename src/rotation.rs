@@ -0,0 +1,628 @@
+use chrono::{DateTime, Datelike, Local, NaiveDateTime, TimeDelta, TimeZone, Timelike, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::retention::{self, size_backups, Policy, Sweep};
+use crate::types::{OutputConfig, RotateEvent, RotateReason, RotationTimezone};
+
+pub const DEFAULT_MAX_FILE_SIZE: i64 = 10 * 1024 * 1024; // 10 MB
+const DEFAULT_SIZE_BACKUPS: u8 = 3;
+const DEFAULT_TIME_BACKUPS: u8 = 7;
+
+// strftime specifiers allowed in `filePattern`, with what they expand to.
+const SPECIFIERS: [(char, &str); 7] = [
+    ('Y', r"\d{4}"),
+    ('m', r"\d{2}"),
+    ('d', r"\d{2}"),
+    ('H', r"\d{2}"),
+    ('M', r"\d{2}"),
+    ('S', r"\d{2}"),
+    ('j', r"\d{3}"),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Period {
+    Minutes(u32),
+    Hourly,
+    Daily,
+    Weekly,
+}
+
+impl Period {
+    pub fn of(output: &OutputConfig) -> Option<Period> {
+        if let Some(minutes) = output.rotate_every_minutes {
+            Some(Period::Minutes(minutes.max(1)))
+        } else if output.rotate_hourly.unwrap_or(false) {
+            Some(Period::Hourly)
+        } else if output.rotate_daily.unwrap_or(false) {
+            Some(Period::Daily)
+        } else if output.rotate_weekly.unwrap_or(false) {
+            Some(Period::Weekly)
+        } else {
+            None
+        }
+    }
+
+    // Periods are aligned to midnight (Monday midnight for weekly), so
+    // `Minutes(15)` starts files at :00, :15, :30 and :45.
+    fn start(&self, now: NaiveDateTime) -> NaiveDateTime {
+        let midnight = now.date().and_hms_opt(0, 0, 0).unwrap_or(now);
+        match self {
+            Period::Minutes(minutes) => {
+                let elapsed = now.hour() * 60 + now.minute();
+                midnight + TimeDelta::minutes(i64::from(elapsed / minutes * minutes))
+            }
+            Period::Hourly => midnight + TimeDelta::hours(i64::from(now.hour())),
+            Period::Daily => midnight,
+            Period::Weekly => {
+                midnight - TimeDelta::days(i64::from(now.weekday().num_days_from_monday()))
+            }
+        }
+    }
+
+    fn reason(&self) -> RotateReason {
+        match self {
+            Period::Minutes(_) => RotateReason::Interval,
+            Period::Hourly => RotateReason::Hourly,
+            Period::Daily => RotateReason::Daily,
+            Period::Weekly => RotateReason::Weekly,
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Period::Minutes(minutes) => format!("{}-minute", minutes),
+            Period::Hourly => "hourly".to_string(),
+            Period::Daily => "daily".to_string(),
+            Period::Weekly => "weekly".to_string(),
+        }
+    }
+
+    fn stamp(&self) -> &'static str {
+        match self {
+            Period::Minutes(_) => "%Y-%m-%d_%H-%M",
+            Period::Hourly => "%Y-%m-%d_%H",
+            Period::Daily | Period::Weekly => "%Y-%m-%d",
+        }
+    }
+}
+
+// The wall-clock time periods are measured in: UTC unless `rotationTimezone`
+// is `local`.
+fn period_time(output: &OutputConfig, now: DateTime<Utc>) -> NaiveDateTime {
+    match output.rotation_timezone {
+        Some(RotationTimezone::Local) => wall_clock(now, &Local),
+        _ => wall_clock(now, &Utc),
+    }
+}
+
+fn wall_clock<Tz: TimeZone>(now: DateTime<Utc>, zone: &Tz) -> NaiveDateTime {
+    now.with_timezone(zone).naive_local()
+}
+
+// The period file currently written to, so the directory is only scanned
+// when the period changes.
+struct ActiveFile {
+    dir: PathBuf,
+    name: String,
+    index: u32,
+}
+
+static ACTIVE: Lazy<Mutex<Option<ActiveFile>>> = Lazy::new(|| Mutex::new(None));
+
+fn directory(file_path: &str) -> PathBuf {
+    match Path::new(file_path).parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+// `app.log` becomes `app_%Y-%m-%d.log` (`app_%Y-%m-%d_%H.log` hourly,
+// `app_%Y-%m-%d_%H-%M.log` for minute intervals) unless `filePattern` names
+// the files explicitly.
+fn file_pattern(output: &OutputConfig, period: Period) -> String {
+    if let Some(pattern) = &output.file_pattern {
+        return pattern.clone();
+    }
+
+    let base = Path::new(output.file_path.as_deref().unwrap_or_default());
+    let stem = base.file_stem().and_then(|s| s.to_str()).unwrap_or("log");
+    let ext = base.extension().and_then(|s| s.to_str()).unwrap_or("log");
+
+    format!(
+        "{}_{}.{}",
+        stem.replace('%', "%%"),
+        period.stamp(),
+        ext.replace('%', "%%")
+    )
+}
+
+fn split_extension(name: &str) -> (&str, Option<&str>) {
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, Some(ext)),
+        _ => (name, None),
+    }
+}
+
+// Size rotation within a period inserts an index before the extension:
+// `app-2026-10-18.log`, `app-2026-10-18.1.log`, `app-2026-10-18.2.log`, ...
+fn indexed(name: &str, index: u32) -> String {
+    if index == 0 {
+        return name.to_string();
+    }
+    match split_extension(name) {
+        (stem, Some(ext)) => format!("{}.{}.{}", stem, index, ext),
+        (stem, None) => format!("{}.{}", stem, index),
+    }
+}
+
+pub fn validate_pattern(pattern: &str) -> Result<(), String> {
+    if pattern.is_empty() || pattern.contains(['/', '\\']) {
+        return Err("must be a file name without directories".to_string());
+    }
+
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            continue;
+        }
+        match chars.next() {
+            Some('%') => {}
+            Some(spec) if SPECIFIERS.iter().any(|(s, _)| *s == spec) => {}
+            Some(spec) => return Err(format!("uses unsupported specifier '%{}'", spec)),
+            None => return Err("ends with a lone '%'".to_string()),
+        }
+    }
+
+    Ok(())
+}
+
+// The pattern has to name every period of the schedule differently: a date
+// (`%Y` with `%m` and `%d`, or `%j`), plus `%H` and `%M` where periods are
+// shorter than a day or an hour. Otherwise a later period would write to the
+// file of an earlier one.
+pub fn validate_pattern_period(pattern: &str, period: Period) -> Result<(), String> {
+    let mut used = Vec::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c == '%' {
+            used.extend(chars.next());
+        }
+    }
+    let uses = |spec: char| used.contains(&spec);
+
+    let mut needed = vec!['Y'];
+    if !uses('j') {
+        needed.extend(['m', 'd']);
+    }
+    match period {
+        Period::Minutes(minutes) => {
+            if minutes < 24 * 60 {
+                needed.push('H');
+            }
+            if minutes % 60 != 0 {
+                needed.push('M');
+            }
+        }
+        Period::Hourly => needed.push('H'),
+        Period::Daily | Period::Weekly => {}
+    }
+
+    let missing: Vec<String> = needed
+        .into_iter()
+        .filter(|spec| !uses(*spec))
+        .map(|spec| format!("%{}", spec))
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "cannot tell {} files apart without {}",
+        period.describe(),
+        missing.join(", ")
+    ))
+}
+
+// Matches every file the pattern can produce, including size-rotation indexes
+// and compressed copies.
+fn pattern_regex(pattern: &str) -> Option<Regex> {
+    let to_regex = |part: &str| {
+        let mut regex = String::new();
+        let mut chars = part.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                regex.push_str(&regex::escape(&c.to_string()));
+                continue;
+            }
+            match chars.next() {
+                Some('%') => regex.push('%'),
+                Some(spec) => regex.push_str(
+                    SPECIFIERS
+                        .iter()
+                        .find(|(s, _)| *s == spec)
+                        .map_or("", |(_, r)| *r),
+                ),
+                None => {}
+            }
+        }
+        regex
+    };
+
+    let regex = match split_extension(pattern) {
+        (stem, Some(ext)) => format!(r"^{}(\.\d+)?\.{}(\.gz)?$", to_regex(stem), to_regex(ext)),
+        (stem, None) => format!(r"^{}(\.\d+)?(\.gz)?$", to_regex(stem)),
+    };
+    Regex::new(&regex).ok()
+}
+
+fn highest_index(dir: &Path, name: &str) -> u32 {
+    let (stem, ext) = split_extension(name);
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+
+    entries
+        .filter_map(|entry| {
+            let file_name = entry.ok()?.file_name().into_string().ok()?;
+            let rest = file_name.strip_prefix(stem)?.strip_prefix('.')?;
+            let index = match ext {
+                Some(ext) => rest.strip_suffix(ext)?.strip_suffix('.')?,
+                None => rest,
+            };
+            index.parse::<u32>().ok()
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+fn exceeds_size(path: &Path, max_size: i64) -> bool {
+    max_size >= 0 && fs::metadata(path).is_ok_and(|metadata| metadata.len() >= max_size as u64)
+}
+
+// The size at which the file is rotated. Time-based rotation only has one
+// when `maxFileSize` is set explicitly.
+pub fn size_limit(output: &OutputConfig) -> Option<u64> {
+    let max_size = match Period::of(output) {
+        Some(_) => output.max_file_size?,
+        None => output.max_file_size.unwrap_or(DEFAULT_MAX_FILE_SIZE),
+    };
+    u64::try_from(max_size).ok()
+}
+
+// `maxBackups` counts the live file in time-based mode, even before it exists.
+fn schedule_time_retention(output: &OutputConfig, dir: &Path, pattern: &str, current: PathBuf) {
+    let Some(backups) = pattern_regex(pattern) else {
+        return;
+    };
+    let max_backups = output.max_backups.unwrap_or(DEFAULT_TIME_BACKUPS).max(1);
+
+    retention::schedule(Sweep {
+        dir: dir.to_path_buf(),
+        backups,
+        current,
+        policy: Policy::new(output, max_backups as usize - 1),
+    });
+}
+
+// Rotations waiting to be reported once the file sink has closed the old file.
+static ROTATIONS: Mutex<Vec<RotateEvent>> = Mutex::new(Vec::new());
+
+fn record(old_path: &Path, new_path: &Path, reason: RotateReason) {
+    ROTATIONS
+        .lock()
+        .expect("Rotations mutex poisoned")
+        .push(RotateEvent {
+            old_path: old_path.display().to_string(),
+            new_path: new_path.display().to_string(),
+            reason,
+        });
+}
+
+pub fn take_rotations() -> Vec<RotateEvent> {
+    std::mem::take(&mut *ROTATIONS.lock().expect("Rotations mutex poisoned"))
+}
+
+impl ActiveFile {
+    fn path(&self) -> PathBuf {
+        self.dir.join(indexed(&self.name, self.index))
+    }
+
+    // Moves on to the next index within the period.
+    fn split(&mut self, output: &OutputConfig, pattern: &str, reason: RotateReason) -> PathBuf {
+        let old = self.path();
+        self.index += 1;
+        let path = self.path();
+        schedule_time_retention(output, &self.dir, pattern, path.clone());
+        record(&old, &path, reason);
+        path
+    }
+}
+
+fn time_rotated_path(
+    output: &OutputConfig,
+    file_path: &str,
+    period: Period,
+    now: DateTime<Utc>,
+) -> PathBuf {
+    let dir = directory(file_path);
+    let pattern = file_pattern(output, period);
+    // Files are named after the start of their period, not the time of the write.
+    let name = period
+        .start(period_time(output, now))
+        .format(&pattern)
+        .to_string();
+
+    let mut active = ACTIVE.lock().expect("Active file mutex poisoned");
+    let current = match active.as_mut() {
+        Some(current) if current.dir == dir && current.name == name => current,
+        _ => {
+            // A file from an earlier period in the same directory was rotated;
+            // a changed `filePath` was not.
+            let previous = active
+                .as_ref()
+                .filter(|previous| previous.dir == dir)
+                .map(ActiveFile::path);
+            let index = highest_index(&dir, &name);
+            let current = active.insert(ActiveFile { dir, name, index });
+            schedule_time_retention(output, &current.dir, &pattern, current.path());
+            if let Some(previous) = previous {
+                record(&previous, &current.path(), period.reason());
+            }
+            current
+        }
+    };
+
+    // Size limits only apply within a period when they are set explicitly.
+    if let Some(max_size) = output.max_file_size {
+        if exceeds_size(&current.path(), max_size) {
+            current.split(output, &pattern, RotateReason::Size);
+        }
+    }
+
+    current.path()
+}
+
+// Size-only rotation shifts `app.log` to `app.log.1`, `app.log.1` to `app.log.2`, ...
+fn rotate_backups(path: &str, max_backups: u8) {
+    for i in (1..=max_backups).rev() {
+        let src = if i == 1 {
+            path.to_string()
+        } else {
+            format!("{}.{}", path, i - 1)
+        };
+        let dst = format!("{}.{}", path, i);
+
+        if Path::new(&src).exists() {
+            let _ = fs::rename(src, dst);
+        }
+    }
+}
+
+fn rotate_size_backups(output: &OutputConfig, file_path: &str, reason: RotateReason) {
+    let max_backups = output.max_backups.unwrap_or(DEFAULT_SIZE_BACKUPS);
+    rotate_backups(file_path, max_backups);
+
+    if let Some(backups) = size_backups(Path::new(file_path)) {
+        retention::schedule(Sweep {
+            dir: directory(file_path),
+            backups,
+            current: PathBuf::from(file_path),
+            policy: Policy::new(output, max_backups as usize),
+        });
+    }
+    if max_backups > 0 {
+        let rotated = format!("{}.1", file_path);
+        record(Path::new(&rotated), Path::new(file_path), reason);
+    }
+}
+
+fn file_path(output: &OutputConfig) -> Result<&str, String> {
+    output
+        .file_path
+        .as_deref()
+        .filter(|path| !path.is_empty())
+        .ok_or_else(|| "no file path provided for log output".to_string())
+}
+
+// Resolves the file to write to, rotating first when a period or size limit is reached.
+pub fn current_path(output: &OutputConfig) -> Result<PathBuf, String> {
+    let file_path = file_path(output)?;
+
+    if let Some(period) = Period::of(output) {
+        return Ok(time_rotated_path(output, file_path, period, Utc::now()));
+    }
+
+    let max_size = output.max_file_size.unwrap_or(DEFAULT_MAX_FILE_SIZE);
+    if exceeds_size(Path::new(file_path), max_size) {
+        rotate_size_backups(output, file_path, RotateReason::Size);
+    }
+
+    Ok(PathBuf::from(file_path))
+}
+
+// Called from the writer thread's timer so that a new period starts on time
+// even when nothing is logged. Before the first write there is nothing to
+// rotate. Returns the new file if a period ended.
+pub fn rotate_if_due(output: &OutputConfig) -> Option<PathBuf> {
+    rotate_if_due_at(output, Utc::now())
+}
+
+fn rotate_if_due_at(output: &OutputConfig, now: DateTime<Utc>) -> Option<PathBuf> {
+    let period = Period::of(output)?;
+    let file_path = file_path(output).ok()?;
+
+    let name = period
+        .start(period_time(output, now))
+        .format(&file_pattern(output, period))
+        .to_string();
+    let due = ACTIVE
+        .lock()
+        .expect("Active file mutex poisoned")
+        .as_ref()
+        .is_some_and(|active| active.name != name);
+
+    due.then(|| time_rotated_path(output, file_path, period, now))
+}
+
+// Starts a new file on request: the next index within the period with
+// time-based rotation, or the next backup with size rotation. Returns false
+// when the file does not exist yet, so there is nothing to rotate.
+pub fn rotate_now(output: &OutputConfig) -> Result<bool, String> {
+    let file_path = file_path(output)?;
+
+    let Some(period) = Period::of(output) else {
+        if !Path::new(file_path).exists() {
+            return Ok(false);
+        }
+        rotate_size_backups(output, file_path, RotateReason::Manual);
+        return Ok(true);
+    };
+
+    if !time_rotated_path(output, file_path, period, Utc::now()).exists() {
+        return Ok(false);
+    }
+    let mut active = ACTIVE.lock().expect("Active file mutex poisoned");
+    let Some(current) = active.as_mut() else {
+        return Ok(false);
+    };
+    current.split(output, &file_pattern(output, period), RotateReason::Manual);
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, NaiveDate};
+    use serde_json::json;
+
+    fn at(date: (i32, u32, u32), time: (u32, u32, u32)) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(date.0, date.1, date.2)
+            .and_then(|day| day.and_hms_opt(time.0, time.1, time.2))
+            .unwrap()
+    }
+
+    fn output(fields: serde_json::Value) -> OutputConfig {
+        let mut config = json!({ "format": "json", "target": "file" });
+        config
+            .as_object_mut()
+            .unwrap()
+            .extend(fields.as_object().unwrap().clone());
+        serde_json::from_value(config).unwrap()
+    }
+
+    #[test]
+    fn aligns_minute_periods_to_midnight() {
+        let now = at((2026, 10, 18), (13, 47, 59));
+
+        assert_eq!(
+            Period::Minutes(15).start(now),
+            at((2026, 10, 18), (13, 45, 0))
+        );
+        assert_eq!(
+            Period::Minutes(1).start(now),
+            at((2026, 10, 18), (13, 47, 0))
+        );
+        // 13:47 is minute 827 of the day; 7-minute periods count from midnight,
+        // so the current one started at minute 826.
+        assert_eq!(
+            Period::Minutes(7).start(now),
+            at((2026, 10, 18), (13, 46, 0))
+        );
+        assert_eq!(
+            Period::Minutes(90).start(now),
+            at((2026, 10, 18), (13, 30, 0))
+        );
+        assert_eq!(
+            Period::Minutes(1440).start(now),
+            at((2026, 10, 18), (0, 0, 0))
+        );
+    }
+
+    #[test]
+    fn starts_hourly_and_daily_periods_on_the_hour_and_midnight() {
+        let now = at((2026, 10, 18), (13, 47, 59));
+
+        assert_eq!(Period::Hourly.start(now), at((2026, 10, 18), (13, 0, 0)));
+        assert_eq!(Period::Daily.start(now), at((2026, 10, 18), (0, 0, 0)));
+    }
+
+    #[test]
+    fn starts_weekly_periods_on_monday() {
+        // 2026-10-18 is a Sunday, 2026-10-19 a Monday.
+        let monday = at((2026, 10, 12), (0, 0, 0));
+        assert_eq!(
+            Period::Weekly.start(at((2026, 10, 18), (23, 59, 59))),
+            monday
+        );
+        assert_eq!(Period::Weekly.start(at((2026, 10, 12), (0, 0, 0))), monday);
+        assert_eq!(
+            Period::Weekly.start(at((2026, 10, 19), (8, 0, 0))),
+            at((2026, 10, 19), (0, 0, 0))
+        );
+        // Across a month and a year boundary.
+        assert_eq!(
+            Period::Weekly.start(at((2027, 1, 1), (12, 0, 0))),
+            at((2026, 12, 28), (0, 0, 0))
+        );
+    }
+
+    #[test]
+    fn measures_periods_in_the_configured_zone() {
+        let now = Utc.from_utc_datetime(&at((2026, 10, 18), (23, 30, 0)));
+        let tokyo = FixedOffset::east_opt(9 * 3600).unwrap();
+
+        assert_eq!(
+            Period::Daily.start(wall_clock(now, &Utc)),
+            at((2026, 10, 18), (0, 0, 0))
+        );
+        assert_eq!(
+            Period::Daily.start(wall_clock(now, &tokyo)),
+            at((2026, 10, 19), (0, 0, 0))
+        );
+        assert_eq!(
+            period_time(&output(json!({ "rotationTimezone": "utc" })), now),
+            at((2026, 10, 18), (23, 30, 0))
+        );
+        assert_eq!(
+            period_time(&output(json!({ "rotationTimezone": "local" })), now),
+            now.with_timezone(&Local).naive_local()
+        );
+    }
+
+    #[test]
+    fn rotates_on_the_timer_at_the_boundary_without_writes() {
+        let dir = std::env::temp_dir().join(format!("eventum-rotation-{}", std::process::id()));
+        let file_path = dir.join("app.log");
+        let output = output(json!({
+            "filePath": file_path.to_string_lossy(),
+            "rotateEveryMinutes": 1
+        }));
+        let clock = |time| Utc.from_utc_datetime(&at((2026, 10, 18), time));
+
+        // Nothing has been written yet, so there is nothing to rotate.
+        assert_eq!(rotate_if_due_at(&output, clock((13, 47, 10))), None);
+
+        let first = time_rotated_path(
+            &output,
+            &file_path.to_string_lossy(),
+            Period::Minutes(1),
+            clock((13, 47, 10)),
+        );
+        assert_eq!(first, dir.join("app_2026-10-18_13-47.log"));
+        assert_eq!(rotate_if_due_at(&output, clock((13, 47, 59))), None);
+
+        let next = rotate_if_due_at(&output, clock((13, 48, 0)));
+        assert_eq!(next, Some(dir.join("app_2026-10-18_13-48.log")));
+        assert_eq!(rotate_if_due_at(&output, clock((13, 48, 30))), None);
+
+        let events = take_rotations();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].old_path, first.display().to_string());
+        assert_eq!(events[0].reason, RotateReason::Interval);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
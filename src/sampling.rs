@@ -0,0 +1,312 @@
+use serde_json::{Map, Value};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::directives::{scope_matches, specificity};
+use crate::levels::{Level, LevelRegistry};
+use crate::types::{LogLevel, SamplingConfig};
+use crate::utils::{extract_scope_and_value, scope_of};
+
+// Distinct messages tracked for deduplication at once. Beyond this, new
+// messages are logged without being tracked rather than growing the map.
+const MAX_TRACKED_MESSAGES: usize = 10_000;
+
+struct Bucket {
+    tokens: f64,
+    refilled: Instant,
+    dropped: u64,
+}
+
+impl Bucket {
+    // Tops the bucket up for the time since it was last refilled.
+    fn refill(&mut self, now: Instant, rate: f64) {
+        let elapsed = now.duration_since(self.refilled).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(rate);
+        self.refilled = now;
+    }
+}
+
+struct Repeat {
+    until: Instant,
+    count: u64,
+    level: Level,
+    message: Value,
+}
+
+#[derive(Default)]
+struct State {
+    buckets: HashMap<Option<String>, Bucket>,
+    repeats: HashMap<u64, Repeat>,
+    next_sweep: Option<Instant>,
+}
+
+// Rates are fixed for a config, so sampling alone never locks; only rate
+// limiting and deduplication share the mutable state.
+#[derive(Default)]
+pub struct Sampler {
+    level_rates: Vec<(String, f64)>,
+    scope_rates: Vec<(String, f64)>,
+    max_per_second: Option<f64>,
+    dedupe_window: Option<Duration>,
+    state: Mutex<State>,
+}
+
+fn fingerprint(level: &Level, message: &Value) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    level.name.hash(&mut hasher);
+    hash_value(message, &mut hasher);
+    hasher.finish()
+}
+
+fn hash_value(value: &Value, hasher: &mut impl Hasher) {
+    std::mem::discriminant(value).hash(hasher);
+    match value {
+        Value::Null => {}
+        Value::Bool(b) => b.hash(hasher),
+        Value::Number(n) => n.hash(hasher),
+        Value::String(s) => s.hash(hasher),
+        Value::Array(items) => {
+            items.len().hash(hasher);
+            for item in items {
+                hash_value(item, hasher);
+            }
+        }
+        Value::Object(map) => {
+            map.len().hash(hasher);
+            for (key, item) in map {
+                key.hash(hasher);
+                hash_value(item, hasher);
+            }
+        }
+    }
+}
+
+// A summary keeps the scope of the entries it stands for, so it is filtered,
+// routed and labelled like them.
+fn summary(scope: Option<String>, text: String) -> Value {
+    match scope {
+        Some(scope) => {
+            let mut map = Map::new();
+            map.insert("scope".to_string(), Value::from(scope));
+            map.insert("message".to_string(), Value::from(text));
+            Value::Object(map)
+        }
+        None => Value::from(text),
+    }
+}
+
+fn repeated_summary(repeat: Repeat) -> (Level, Value) {
+    let (scope, message) = extract_scope_and_value(&repeat.message);
+    let text = match message {
+        Value::String(s) => s,
+        Value::Object(map) if map.get("message").is_some_and(Value::is_string) => {
+            map["message"].as_str().unwrap_or_default().to_string()
+        }
+        other => other.to_string(),
+    };
+
+    let text = format!("message repeated {} times: {}", repeat.count, text);
+    (repeat.level, summary(scope, text))
+}
+
+fn dropped_summary(scope: Option<String>, dropped: u64) -> (Level, Value) {
+    let text = format!("rate limit exceeded: {} entries dropped", dropped);
+    (LogLevel::Warn.into(), summary(scope, text))
+}
+
+impl Sampler {
+    pub fn new(config: &SamplingConfig, levels: &LevelRegistry) -> Self {
+        let level_rates = config
+            .sample
+            .iter()
+            .flatten()
+            .filter_map(|(name, rate)| Some((levels.lookup(name)?.name.to_string(), *rate)))
+            .collect();
+
+        let mut scope_rates: Vec<(String, f64)> = config
+            .scopes
+            .iter()
+            .flatten()
+            .map(|(pattern, rate)| (pattern.clone(), *rate))
+            .collect();
+        scope_rates.sort_by_key(|(pattern, _)| std::cmp::Reverse(specificity(pattern)));
+
+        Sampler {
+            level_rates,
+            scope_rates,
+            max_per_second: config.max_per_second.map(f64::from),
+            dedupe_window: config
+                .dedupe_window_ms
+                .map(|ms| Duration::from_millis(ms.max(0) as u64)),
+            ..Sampler::default()
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.level_rates.is_empty()
+            || !self.scope_rates.is_empty()
+            || self.max_per_second.is_some()
+            || self.dedupe_window.is_some()
+    }
+
+    fn sampled(&self, level: &Level, scope: Option<&str>) -> bool {
+        let level_rate = self
+            .level_rates
+            .iter()
+            .find(|(name, _)| *name == level.name)
+            .map_or(1.0, |(_, rate)| *rate);
+        let scope_rate = scope
+            .and_then(|scope| {
+                self.scope_rates
+                    .iter()
+                    .find(|(pattern, _)| scope_matches(pattern, scope))
+            })
+            .map_or(1.0, |(_, rate)| *rate);
+
+        let rate = level_rate * scope_rate;
+        rate >= 1.0 || fastrand::f64() < rate
+    }
+
+    // Summaries that are due: dedupe windows that have ended with repeats,
+    // and drops from buckets that have refilled since.
+    fn due(&self, state: &mut State, now: Instant, summaries: &mut Vec<(Level, Value)>) {
+        if let Some(rate) = self.max_per_second {
+            for (scope, bucket) in &mut state.buckets {
+                if bucket.dropped == 0 {
+                    continue;
+                }
+                bucket.refill(now, rate);
+                if bucket.tokens >= 1.0 {
+                    summaries.push(dropped_summary(
+                        scope.clone(),
+                        std::mem::take(&mut bucket.dropped),
+                    ));
+                }
+            }
+        }
+
+        if state.next_sweep.is_none_or(|at| now < at) {
+            return;
+        }
+
+        let expired: Vec<u64> = state
+            .repeats
+            .iter()
+            .filter(|(_, repeat)| repeat.until <= now)
+            .map(|(key, _)| *key)
+            .collect();
+        for key in expired {
+            if let Some(repeat) = state.repeats.remove(&key) {
+                if repeat.count > 0 {
+                    summaries.push(repeated_summary(repeat));
+                }
+            }
+        }
+
+        state.next_sweep = state.repeats.values().map(|repeat| repeat.until).min();
+    }
+
+    // Returns whether an entry should be logged. Summaries of entries suppressed
+    // earlier are pushed to `summaries` and should be logged before it.
+    pub fn admit(
+        &self,
+        level: &Level,
+        message: &Value,
+        summaries: &mut Vec<(Level, Value)>,
+    ) -> bool {
+        let scope = scope_of(message);
+        if !self.sampled(level, scope) {
+            return false;
+        }
+        if self.max_per_second.is_none() && self.dedupe_window.is_none() {
+            return true;
+        }
+
+        let key = self.dedupe_window.map(|_| fingerprint(level, message));
+        let now = Instant::now();
+        let mut state = self.state.lock().expect("Sampler mutex poisoned");
+        self.due(&mut state, now, summaries);
+
+        if let Some(repeat) = key.and_then(|key| state.repeats.get_mut(&key)) {
+            repeat.count += 1;
+            return false;
+        }
+
+        if let Some(rate) = self.max_per_second {
+            // One token bucket per scope, holding at most one second worth of entries.
+            let bucket = state
+                .buckets
+                .entry(scope.map(str::to_string))
+                .or_insert(Bucket {
+                    tokens: rate,
+                    refilled: now,
+                    dropped: 0,
+                });
+            bucket.refill(now, rate);
+
+            if bucket.tokens < 1.0 {
+                bucket.dropped += 1;
+                return false;
+            }
+            bucket.tokens -= 1.0;
+        }
+
+        // Only admitted entries start a dedupe window, so an entry dropped by
+        // the rate limit does not suppress its repeats.
+        if let (Some(key), Some(window)) = (key, self.dedupe_window) {
+            if state.repeats.len() < MAX_TRACKED_MESSAGES {
+                let until = now + window;
+                state.repeats.insert(
+                    key,
+                    Repeat {
+                        until,
+                        count: 0,
+                        level: level.clone(),
+                        message: message.clone(),
+                    },
+                );
+                state.next_sweep = Some(state.next_sweep.map_or(until, |at| at.min(until)));
+            }
+        }
+
+        true
+    }
+
+    // Summaries that are due without anything being logged, for the writer
+    // thread's timer.
+    pub fn tick(&self) -> Vec<(Level, Value)> {
+        let mut summaries = Vec::new();
+        if self.max_per_second.is_some() || self.dedupe_window.is_some() {
+            let mut state = self.state.lock().expect("Sampler mutex poisoned");
+            self.due(&mut state, Instant::now(), &mut summaries);
+        }
+        summaries
+    }
+
+    // Summaries for everything still suppressed, e.g. at shutdown.
+    pub fn drain(&self) -> Vec<(Level, Value)> {
+        let mut state = self.state.lock().expect("Sampler mutex poisoned");
+        let mut summaries: Vec<(Level, Value)> = state
+            .repeats
+            .drain()
+            .map(|(_, repeat)| repeat)
+            .filter(|repeat| repeat.count > 0)
+            .map(repeated_summary)
+            .collect();
+        state.next_sweep = None;
+
+        for (scope, bucket) in &mut state.buckets {
+            if bucket.dropped > 0 {
+                summaries.push(dropped_summary(
+                    scope.clone(),
+                    std::mem::take(&mut bucket.dropped),
+                ));
+            }
+        }
+
+        summaries
+    }
+}
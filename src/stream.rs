@@ -0,0 +1,415 @@
+use once_cell::sync::OnceCell;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, ServerName};
+use rustls::{ClientConfig, ClientConnection, RootCertStore, StreamOwned};
+use std::collections::VecDeque;
+use std::io::Write;
+use std::net::{TcpStream, ToSocketAddrs};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::types::{OutputTarget, StreamConfig};
+
+const DEFAULT_CONNECT_TIMEOUT_MS: i64 = 5_000;
+const DEFAULT_RECONNECT_BASE_MS: i64 = 500;
+const DEFAULT_RECONNECT_MAX_MS: i64 = 30_000;
+const DEFAULT_SPILL_BUFFER_SIZE: u32 = 10_000;
+
+static STREAM: OnceCell<Mutex<Option<StreamSink>>> = OnceCell::new();
+
+enum Connection {
+    Tcp(TcpStream),
+    Tls(Box<StreamOwned<ClientConnection, TcpStream>>),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+impl Connection {
+    fn open(
+        target: StreamTarget,
+        config: &StreamConfig,
+        timeout: Duration,
+    ) -> std::io::Result<Self> {
+        match target {
+            StreamTarget::Tcp => {
+                let stream = connect_tcp(&config.address, timeout)?;
+                // A stalled collector must not block the writing thread forever.
+                stream.set_write_timeout(Some(timeout))?;
+                stream.set_read_timeout(Some(timeout))?;
+                let _ = stream.set_nodelay(true);
+
+                if !config.tls.unwrap_or(false) {
+                    return Ok(Connection::Tcp(stream));
+                }
+
+                let tls = tls_client_config(config)?;
+                let server_name = tls_server_name(config)?;
+                let connection = ClientConnection::new(tls, server_name).map_err(invalid_data)?;
+                let mut tls_stream = StreamOwned::new(connection, stream);
+                // Complete the handshake now so certificate errors surface on connect.
+                while tls_stream.conn.is_handshaking() {
+                    tls_stream.conn.complete_io(&mut tls_stream.sock)?;
+                }
+                Ok(Connection::Tls(Box::new(tls_stream)))
+            }
+            #[cfg(unix)]
+            StreamTarget::Unix => {
+                let stream = UnixStream::connect(&config.address)?;
+                stream.set_write_timeout(Some(timeout))?;
+                Ok(Connection::Unix(stream))
+            }
+            #[cfg(not(unix))]
+            StreamTarget::Unix => Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "Unix sockets are not supported on this platform",
+            )),
+        }
+    }
+
+    fn write_all(&mut self, data: &[u8]) -> std::io::Result<()> {
+        match self {
+            Connection::Tcp(stream) => stream.write_all(data),
+            Connection::Tls(stream) => {
+                stream.write_all(data)?;
+                stream.flush()
+            }
+            #[cfg(unix)]
+            Connection::Unix(stream) => stream.write_all(data),
+        }
+    }
+}
+
+fn connect_tcp(address: &str, timeout: Duration) -> std::io::Result<TcpStream> {
+    let mut last_err = std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        format!("could not resolve {}", address),
+    );
+
+    for addr in address.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(err) => last_err = err,
+        }
+    }
+
+    Err(last_err)
+}
+
+fn tls_client_config(config: &StreamConfig) -> std::io::Result<Arc<ClientConfig>> {
+    let mut roots = RootCertStore::empty();
+
+    match &config.tls_ca_file {
+        Some(path) => {
+            let certs = CertificateDer::pem_file_iter(path)
+                .map_err(invalid_data)?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(invalid_data)?;
+            let (added, _) = roots.add_parsable_certificates(certs);
+            if added == 0 {
+                return Err(invalid_data(format!("no certificates found in {}", path)));
+            }
+        }
+        None => roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned()),
+    }
+
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let tls = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(invalid_data)?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+
+    Ok(Arc::new(tls))
+}
+
+fn tls_server_name(config: &StreamConfig) -> std::io::Result<ServerName<'static>> {
+    let name = match &config.tls_server_name {
+        Some(name) => name.clone(),
+        None => host_of(&config.address).to_string(),
+    };
+
+    ServerName::try_from(name).map_err(invalid_data)
+}
+
+// Strips the port from "host:port" and the brackets from "[::1]:port".
+fn host_of(address: &str) -> &str {
+    let host = match address.rsplit_once(':') {
+        Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => host,
+        _ => address,
+    };
+    host.trim_start_matches('[').trim_end_matches(']')
+}
+
+fn invalid_data(err: impl ToString) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, err.to_string())
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum StreamTarget {
+    Tcp,
+    Unix,
+}
+
+impl StreamTarget {
+    pub fn from_output(target: &OutputTarget) -> Option<Self> {
+        match target {
+            OutputTarget::Tcp => Some(StreamTarget::Tcp),
+            OutputTarget::UnixSocket => Some(StreamTarget::Unix),
+            _ => None,
+        }
+    }
+}
+
+struct StreamSink {
+    target: StreamTarget,
+    config: StreamConfig,
+    connection: Option<Connection>,
+    // Lines written while the connection is down, oldest first.
+    spill: VecDeque<String>,
+    dropped: usize,
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+impl StreamSink {
+    fn new(target: StreamTarget, config: StreamConfig) -> Self {
+        StreamSink {
+            target,
+            config,
+            connection: None,
+            spill: VecDeque::new(),
+            dropped: 0,
+            failures: 0,
+            retry_at: None,
+        }
+    }
+
+    fn matches(&self, target: StreamTarget, config: &StreamConfig) -> bool {
+        self.target == target
+            && self.config.address == config.address
+            && self.config.tls == config.tls
+            && self.config.tls_server_name == config.tls_server_name
+            && self.config.tls_ca_file == config.tls_ca_file
+    }
+
+    fn connect_timeout(&self) -> Duration {
+        Duration::from_millis(
+            self.config
+                .connect_timeout_ms
+                .unwrap_or(DEFAULT_CONNECT_TIMEOUT_MS)
+                .max(1) as u64,
+        )
+    }
+
+    fn backoff(&self) -> Duration {
+        let base = self
+            .config
+            .reconnect_base_ms
+            .unwrap_or(DEFAULT_RECONNECT_BASE_MS)
+            .max(1) as u64;
+        let max = self
+            .config
+            .reconnect_max_ms
+            .unwrap_or(DEFAULT_RECONNECT_MAX_MS)
+            .max(1) as u64;
+        let delay = base
+            .saturating_mul(1 << self.failures.saturating_sub(1).min(20))
+            .min(max);
+
+        Duration::from_millis(delay / 2 + fastrand::u64(0..=delay / 2))
+    }
+
+    fn spill(&mut self, lines: impl IntoIterator<Item = String>) {
+        let capacity = self
+            .config
+            .spill_buffer_size
+            .unwrap_or(DEFAULT_SPILL_BUFFER_SIZE) as usize;
+
+        for line in lines {
+            if capacity == 0 {
+                self.dropped += 1;
+                continue;
+            }
+            if self.spill.len() >= capacity {
+                self.spill.pop_front();
+                self.dropped += 1;
+            }
+            self.spill.push_back(line);
+        }
+    }
+
+    fn disconnect(&mut self, err: std::io::Error) {
+        if self.failures == 0 {
+            eprintln!(
+                "[Logger] Lost connection to {}: {}. Reconnecting with backoff.",
+                self.config.address, err
+            );
+        }
+        self.connection = None;
+        self.failures = self.failures.saturating_add(1);
+        self.retry_at = Some(Instant::now() + self.backoff());
+    }
+
+    // Makes sure a connection is open, reconnecting once the backoff has
+    // elapsed (or right away with `drain_by`, at shutdown), and replays
+    // spilled lines. Connect and write timeouts never reach past `drain_by`.
+    fn ensure_connected(&mut self, drain_by: Option<Instant>) -> bool {
+        if self.connection.is_none() {
+            if drain_by.is_none() && self.retry_at.is_some_and(|at| Instant::now() < at) {
+                return false;
+            }
+
+            let mut timeout = self.connect_timeout();
+            if let Some(deadline) = drain_by {
+                timeout = timeout.min(deadline.saturating_duration_since(Instant::now()));
+                if timeout.is_zero() {
+                    return false;
+                }
+            }
+
+            match Connection::open(self.target, &self.config, timeout) {
+                Ok(connection) => self.connection = Some(connection),
+                Err(err) => {
+                    self.disconnect(err);
+                    return false;
+                }
+            }
+        }
+
+        if !self.spill.is_empty() {
+            let pending: Vec<String> = self.spill.drain(..).collect();
+            if let Err(err) = self.write_lines(&pending) {
+                self.spill.extend(pending);
+                self.disconnect(err);
+                return false;
+            }
+        }
+
+        if self.failures > 0 {
+            if self.dropped > 0 {
+                eprintln!(
+                    "[Logger] Reconnected to {}. {} log entries were dropped while it was down.",
+                    self.config.address, self.dropped
+                );
+            }
+            self.failures = 0;
+            self.dropped = 0;
+            self.retry_at = None;
+        }
+
+        true
+    }
+
+    fn write_lines(&mut self, lines: &[String]) -> std::io::Result<()> {
+        let mut payload = String::with_capacity(lines.iter().map(|line| line.len() + 1).sum());
+        for line in lines {
+            payload.push_str(line);
+            payload.push('\n');
+        }
+
+        match self.connection.as_mut() {
+            Some(connection) => connection.write_all(payload.as_bytes()),
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::NotConnected,
+                "stream connection is down",
+            )),
+        }
+    }
+
+    // One immediate attempt, ignoring the backoff, to replay the spill buffer
+    // by `deadline`; whatever cannot be sent is dropped and reported.
+    fn drain(&mut self, deadline: Instant) {
+        if self.spill.is_empty() || self.ensure_connected(Some(deadline)) {
+            return;
+        }
+
+        eprintln!(
+            "[Logger] Failed to send {} log entries to {}: connection is down. Entries dropped.",
+            self.spill.len() + self.dropped,
+            self.config.address
+        );
+        self.spill.clear();
+        self.dropped = 0;
+    }
+
+    // With `buffer` unset, lines that cannot be written are handed back to
+    // the caller (which has a fallback sink) instead of being spilled.
+    fn send(&mut self, lines: &[String], buffer: bool) -> Result<(), String> {
+        if !self.ensure_connected(None) {
+            if !buffer {
+                return Err(format!("connection to {} is down", self.config.address));
+            }
+            self.spill(lines.iter().cloned());
+            return Ok(());
+        }
+
+        if let Err(err) = self.write_lines(lines) {
+            let reason = format!("failed to write to {}: {}", self.config.address, err);
+            self.disconnect(err);
+            if !buffer {
+                return Err(reason);
+            }
+            // A partial write may duplicate a few lines on replay; losing them is worse.
+            self.spill(lines.iter().cloned());
+        }
+
+        Ok(())
+    }
+}
+
+pub fn stream_output(
+    target: StreamTarget,
+    config: &StreamConfig,
+    lines: &[String],
+    buffer: bool,
+) -> Result<(), String> {
+    if lines.is_empty() {
+        return Ok(());
+    }
+
+    let cell = STREAM.get_or_init(|| Mutex::new(None));
+    let mut slot = cell.lock().expect("Stream sink mutex poisoned");
+
+    let sink = match slot.as_mut() {
+        Some(sink) if sink.matches(target, config) => {
+            sink.config = config.clone();
+            sink
+        }
+        _ => {
+            // Lines spilled for the previous address get one last attempt.
+            if let Some(mut previous) = slot.take() {
+                let deadline = Instant::now() + previous.connect_timeout();
+                previous.drain(deadline);
+            }
+            slot.insert(StreamSink::new(target, config.clone()))
+        }
+    };
+
+    sink.send(lines, buffer)
+}
+
+// Replays spilled lines if the reconnect backoff has elapsed. With `drain_by`
+// (shutdown) it makes one immediate attempt that ends by that instant and
+// reports what could not be sent.
+pub fn retry_pending_stream(drain_by: Option<Instant>) {
+    let Some(cell) = STREAM.get() else {
+        return;
+    };
+    let mut slot = cell.lock().expect("Stream sink mutex poisoned");
+    let Some(sink) = slot.as_mut() else {
+        return;
+    };
+
+    if sink.spill.is_empty() {
+        return;
+    }
+
+    match drain_by {
+        Some(deadline) => sink.drain(deadline),
+        None => {
+            sink.ensure_connected(None);
+        }
+    }
+}
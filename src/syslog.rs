@@ -0,0 +1,236 @@
+use chrono::{DateTime, Local, TimeZone, Utc};
+use once_cell::sync::{Lazy, OnceCell};
+use std::io::Write;
+use std::net::{TcpStream, ToSocketAddrs, UdpSocket};
+#[cfg(unix)]
+use std::os::unix::net::UnixDatagram;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::levels::Level;
+use crate::types::{LogEntry, SyslogConfig, SyslogFormat, SyslogProtocol};
+
+const DEFAULT_UNIX_PATH: &str = "/dev/log";
+const DEFAULT_NET_ADDRESS: &str = "127.0.0.1:514";
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+// Bounds connecting to and writing to a TCP collector, so an unreachable or
+// stalled peer holds up the batch thread for at most this long per attempt.
+const TCP_TIMEOUT: Duration = Duration::from_secs(5);
+const NIL: &str = "-";
+
+static SYSLOG: OnceCell<Mutex<Option<SyslogSink>>> = OnceCell::new();
+
+static HOSTNAME: Lazy<String> =
+    Lazy::new(|| gethostname::gethostname().to_string_lossy().into_owned());
+
+pub const FACILITIES: [(&str, u8); 20] = [
+    ("kern", 0),
+    ("user", 1),
+    ("mail", 2),
+    ("daemon", 3),
+    ("auth", 4),
+    ("syslog", 5),
+    ("lpr", 6),
+    ("news", 7),
+    ("uucp", 8),
+    ("cron", 9),
+    ("authpriv", 10),
+    ("ftp", 11),
+    ("local0", 16),
+    ("local1", 17),
+    ("local2", 18),
+    ("local3", 19),
+    ("local4", 20),
+    ("local5", 21),
+    ("local6", 22),
+    ("local7", 23),
+];
+
+pub fn facility_code(name: &str) -> Option<u8> {
+    FACILITIES
+        .iter()
+        .find(|(facility, _)| facility.eq_ignore_ascii_case(name))
+        .map(|(_, code)| *code)
+}
+
+// Maps Eventum severities (built-in and custom) onto RFC 5424 severities.
+pub fn syslog_severity(level: &Level) -> u8 {
+    match level.severity {
+        0..=29 => 7,  // debug
+        30..=34 => 6, // informational
+        35..=39 => 5, // notice
+        40..=49 => 4, // warning
+        50..=59 => 3, // error
+        _ => 2,       // critical
+    }
+}
+
+enum Connection {
+    #[cfg(unix)]
+    Unix(UnixDatagram),
+    Udp(UdpSocket),
+    Tcp(TcpStream),
+}
+
+impl Connection {
+    fn open(protocol: SyslogProtocol, address: &str) -> std::io::Result<Self> {
+        match protocol {
+            #[cfg(unix)]
+            SyslogProtocol::Unix => {
+                let socket = UnixDatagram::unbound()?;
+                socket.connect(address)?;
+                Ok(Connection::Unix(socket))
+            }
+            #[cfg(not(unix))]
+            SyslogProtocol::Unix => Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "Unix sockets are not supported on this platform",
+            )),
+            SyslogProtocol::Udp => {
+                let socket = UdpSocket::bind("0.0.0.0:0")?;
+                socket.connect(address)?;
+                Ok(Connection::Udp(socket))
+            }
+            SyslogProtocol::Tcp => {
+                let stream = connect_tcp(address)?;
+                stream.set_write_timeout(Some(TCP_TIMEOUT))?;
+                Ok(Connection::Tcp(stream))
+            }
+        }
+    }
+
+    fn send(&mut self, frame: &str) -> std::io::Result<()> {
+        match self {
+            #[cfg(unix)]
+            Connection::Unix(socket) => socket.send(frame.as_bytes()).map(|_| ()),
+            Connection::Udp(socket) => socket.send(frame.as_bytes()).map(|_| ()),
+            // RFC 6587 octet-counting framing.
+            Connection::Tcp(stream) => {
+                stream.write_all(format!("{} {}", frame.len(), frame).as_bytes())
+            }
+        }
+    }
+}
+
+fn connect_tcp(address: &str) -> std::io::Result<TcpStream> {
+    let mut last_err = std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        format!("could not resolve {}", address),
+    );
+
+    for addr in address.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, TCP_TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(err) => last_err = err,
+        }
+    }
+
+    Err(last_err)
+}
+
+struct SyslogSink {
+    protocol: SyslogProtocol,
+    address: String,
+    connection: Option<Connection>,
+    retry_at: Option<Instant>,
+}
+
+impl SyslogSink {
+    fn new(protocol: SyslogProtocol, address: String) -> Self {
+        SyslogSink {
+            protocol,
+            address,
+            connection: None,
+            retry_at: None,
+        }
+    }
+
+    fn send(&mut self, frame: &str) -> std::io::Result<()> {
+        if let Some(connection) = self.connection.as_mut() {
+            if connection.send(frame).is_ok() {
+                return Ok(());
+            }
+            // The peer went away (syslogd restart, TCP reset): reconnect once.
+            self.connection = None;
+        }
+
+        if self.retry_at.is_some_and(|at| Instant::now() < at) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotConnected,
+                "syslog connection is down",
+            ));
+        }
+
+        let result = Connection::open(self.protocol, &self.address).and_then(|mut connection| {
+            connection.send(frame)?;
+            self.connection = Some(connection);
+            Ok(())
+        });
+
+        self.retry_at = result.is_err().then(|| Instant::now() + RECONNECT_DELAY);
+        result
+    }
+}
+
+pub fn format_frame(
+    config: &SyslogConfig,
+    entry: &LogEntry,
+    hostname: &str,
+    message: &str,
+) -> String {
+    let facility = config
+        .facility
+        .as_deref()
+        .and_then(facility_code)
+        .unwrap_or(1);
+    let pri = facility as u32 * 8 + syslog_severity(&entry.level) as u32;
+    let app_name = config.app_name.as_deref().unwrap_or("node");
+    let time = Utc
+        .timestamp_millis_opt(entry.time)
+        .single()
+        .unwrap_or_else(Utc::now);
+
+    match config.format.unwrap_or(SyslogFormat::Rfc5424) {
+        SyslogFormat::Rfc5424 => format!(
+            "<{}>1 {} {} {} {} {} {} {}",
+            pri,
+            time.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            hostname,
+            app_name,
+            entry.pid,
+            config.msgid.as_deref().unwrap_or(NIL),
+            NIL,
+            message
+        ),
+        SyslogFormat::Rfc3164 => format!(
+            "<{}>{} {} {}[{}]: {}",
+            pri,
+            DateTime::<Local>::from(time).format("%b %e %H:%M:%S"),
+            hostname,
+            app_name,
+            entry.pid,
+            message
+        ),
+    }
+}
+
+pub fn syslog_output(config: &SyslogConfig, entry: &LogEntry, message: &str) -> Result<(), String> {
+    let protocol = config.protocol.unwrap_or(SyslogProtocol::Unix);
+    let address = config.address.clone().unwrap_or_else(|| match protocol {
+        SyslogProtocol::Unix => DEFAULT_UNIX_PATH.to_string(),
+        _ => DEFAULT_NET_ADDRESS.to_string(),
+    });
+
+    let frame = format_frame(config, entry, &HOSTNAME, message);
+
+    let cell = SYSLOG.get_or_init(|| Mutex::new(None));
+    let mut slot = cell.lock().expect("Syslog sink mutex poisoned");
+
+    let sink = match slot.as_mut() {
+        Some(sink) if sink.protocol == protocol && sink.address == address => sink,
+        _ => slot.insert(SyslogSink::new(protocol, address)),
+    };
+
+    sink.send(&frame)
+        .map_err(|err| format!("failed to send to syslog {}: {}", sink.address, err))
+}
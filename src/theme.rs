@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::str::FromStr;
+
+use colored::{Color, ColoredString, Colorize};
+use napi::Either;
+use serde_json::Value;
+
+use crate::levels::Level;
+use crate::types::{ColorMode, ColorTheme, CustomLevel, OutputConfig, OutputTarget};
+
+#[derive(Clone, Debug, Default)]
+pub struct Style {
+    pub color: Option<Color>,
+    pub bold: bool,
+    pub dimmed: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+impl FromStr for Style {
+    type Err = String;
+
+    // Accepts a color name optionally combined with modifiers,
+    // e.g. "red", "bold bright red", "dimmed".
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let mut style = Style::default();
+        let mut color_words = Vec::new();
+
+        for word in spec.split_whitespace() {
+            match word.to_lowercase().as_str() {
+                "bold" => style.bold = true,
+                "dim" | "dimmed" => style.dimmed = true,
+                "italic" => style.italic = true,
+                "underline" => style.underline = true,
+                _ => color_words.push(word),
+            }
+        }
+
+        if !color_words.is_empty() {
+            let name = color_words.join(" ");
+            let color = Color::from_str(&name).map_err(|_| format!("Unknown color '{}'", name))?;
+            style.color = Some(color);
+        }
+
+        Ok(style)
+    }
+}
+
+impl Style {
+    // Config validation rejects invalid specs, so only unset ones take the default.
+    fn parse_or(spec: &Option<String>, default: &str) -> Self {
+        spec.as_deref()
+            .unwrap_or(default)
+            .parse()
+            .unwrap_or_default()
+    }
+
+    pub fn paint(&self, text: &str) -> String {
+        let mut painted: ColoredString = text.normal();
+        if let Some(color) = self.color {
+            painted = painted.color(color);
+        }
+        if self.bold {
+            painted = painted.bold();
+        }
+        if self.dimmed {
+            painted = painted.dimmed();
+        }
+        if self.italic {
+            painted = painted.italic();
+        }
+        if self.underline {
+            painted = painted.underline();
+        }
+        painted.to_string()
+    }
+}
+
+impl ColorTheme {
+    // Every style in the theme with its field name.
+    pub fn styles(&self) -> [(&'static str, &Option<String>); 10] {
+        [
+            ("trace", &self.trace),
+            ("debug", &self.debug),
+            ("info", &self.info),
+            ("warn", &self.warn),
+            ("error", &self.error),
+            ("fatal", &self.fatal),
+            ("time", &self.time),
+            ("scope", &self.scope),
+            ("key", &self.key),
+            ("value", &self.value),
+        ]
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Palette {
+    // Keyed by lowercase level name, covering built-in and custom levels.
+    pub levels: HashMap<String, Style>,
+    pub time: Style,
+    pub scope: Style,
+    pub key: Style,
+    pub value: Style,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette::new(&ColorTheme::default(), &[])
+    }
+}
+
+impl Palette {
+    pub fn new(theme: &ColorTheme, custom_levels: &[CustomLevel]) -> Self {
+        let mut levels: HashMap<String, Style> = custom_levels
+            .iter()
+            .map(|level| {
+                let style = Style::parse_or(&level.color, "");
+                (level.name.trim().to_lowercase(), style)
+            })
+            .collect();
+
+        for (name, spec, fallback) in [
+            ("trace", &theme.trace, "bright black"),
+            ("debug", &theme.debug, "cyan"),
+            ("info", &theme.info, "green"),
+            ("warn", &theme.warn, "yellow"),
+            ("error", &theme.error, "red"),
+            ("fatal", &theme.fatal, "bold red"),
+        ] {
+            levels.insert(name.to_string(), Style::parse_or(spec, fallback));
+        }
+
+        Palette {
+            levels,
+            time: Style::parse_or(&theme.time, "dimmed"),
+            scope: Style::parse_or(&theme.scope, "bold"),
+            key: Style::parse_or(&theme.key, "blue"),
+            value: Style::parse_or(&theme.value, "green"),
+        }
+    }
+
+    pub fn level(&self, level: &Level) -> Option<&Style> {
+        self.levels.get(&level.name.to_lowercase())
+    }
+
+    pub fn paint_message(&self, value: &Value) -> String {
+        match value {
+            Value::String(s) => s.clone(),
+            Value::Object(map) if map.len() == 1 => match map.get("message") {
+                Some(Value::String(s)) => s.clone(),
+                _ => self.paint_json(value),
+            },
+            other => self.paint_json(other),
+        }
+    }
+
+    // Pretty-prints a message the same way as `serde_json::to_string_pretty`,
+    // painting object keys and values separately.
+    pub fn paint_json(&self, value: &Value) -> String {
+        let mut out = String::new();
+        self.write_json(value, 0, &mut out);
+        out
+    }
+
+    fn write_json(&self, value: &Value, depth: usize, out: &mut String) {
+        let indent = "  ".repeat(depth + 1);
+        let closing = "  ".repeat(depth);
+
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                out.push_str("{\n");
+                for (i, (key, val)) in map.iter().enumerate() {
+                    out.push_str(&indent);
+                    out.push_str(&self.key.paint(&Value::String(key.clone()).to_string()));
+                    out.push_str(": ");
+                    self.write_json(val, depth + 1, out);
+                    if i + 1 < map.len() {
+                        out.push(',');
+                    }
+                    out.push('\n');
+                }
+                out.push_str(&closing);
+                out.push('}');
+            }
+            Value::Array(items) if !items.is_empty() => {
+                out.push_str("[\n");
+                for (i, item) in items.iter().enumerate() {
+                    out.push_str(&indent);
+                    self.write_json(item, depth + 1, out);
+                    if i + 1 < items.len() {
+                        out.push(',');
+                    }
+                    out.push('\n');
+                }
+                out.push_str(&closing);
+                out.push(']');
+            }
+            Value::Object(_) => out.push_str("{}"),
+            Value::Array(_) => out.push_str("[]"),
+            other => out.push_str(&self.value.paint(&other.to_string())),
+        }
+    }
+}
+
+// Decides once, at setConfig() time, whether text output should be colored.
+// `FORCE_COLOR` and `NO_COLOR` follow the usual CLI conventions.
+pub fn color_enabled(output: &OutputConfig) -> bool {
+    let mode = match &output.color {
+        None | Some(Either::A(false)) | Some(Either::B(ColorMode::Never)) => return false,
+        Some(Either::A(true)) | Some(Either::B(ColorMode::Always)) => ColorMode::Always,
+        Some(Either::B(ColorMode::Auto)) => ColorMode::Auto,
+    };
+
+    if let Some(force) = env_flag("FORCE_COLOR") {
+        return force;
+    }
+
+    if std::env::var("NO_COLOR").is_ok_and(|v| !v.is_empty()) {
+        return false;
+    }
+
+    match mode {
+        ColorMode::Auto => match output.target {
+            OutputTarget::Stdout => std::io::stdout().is_terminal(),
+            OutputTarget::Stderr => std::io::stderr().is_terminal(),
+            _ => false,
+        },
+        _ => true,
+    }
+}
+
+fn env_flag(name: &str) -> Option<bool> {
+    let value = std::env::var(name).ok()?;
+    match value.as_str() {
+        "0" | "false" => Some(false),
+        _ => Some(true),
+    }
+}
@@ -0,0 +1,24 @@
+use std::cell::{Cell, RefCell};
+
+// Every Node.js worker runs its JS on a dedicated OS thread, so thread-locals
+// give each worker its own identity while the logger statics stay shared.
+thread_local! {
+    static THREAD_ID: Cell<u32> = const { Cell::new(0) };
+    static THREAD_NAME: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+pub fn set_current_thread_id(thread_id: u32) {
+    THREAD_ID.with(|id| id.set(thread_id));
+}
+
+pub fn set_current_thread_name(name: Option<String>) {
+    THREAD_NAME.with(|cell| *cell.borrow_mut() = name);
+}
+
+pub fn current_thread_id() -> u32 {
+    THREAD_ID.with(|id| id.get())
+}
+
+pub fn current_thread_name() -> Option<String> {
+    THREAD_NAME.with(|cell| cell.borrow().clone())
+}
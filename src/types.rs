@@ -1,6 +1,11 @@
+use napi::Either;
 use napi_derive::napi;
-use serde::Serialize;
-use serde_json::Value;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::mpsc::SyncSender;
+
+use crate::levels::Level;
 
 #[napi]
 #[derive(Debug, PartialEq, PartialOrd, Eq, Ord, Serialize)]
@@ -14,11 +19,18 @@ pub enum LogLevel {
 }
 
 #[napi]
+#[derive(Debug, PartialEq, Eq, Hash)]
 pub enum OutputTarget {
     Stdout,
     Stderr,
     File,
     Null,
+    Syslog,
+    Http,
+    Tcp,
+    UnixSocket,
+    Journald,
+    Otlp,
 }
 
 #[napi]
@@ -26,25 +38,145 @@ pub enum OutputTarget {
 pub enum OutputFormat {
     Text,
     Json,
+    Logfmt,
+}
+
+#[napi(string_enum)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ColorMode {
+    #[napi(value = "never")]
+    Never,
+    #[napi(value = "always")]
+    Always,
+    #[napi(value = "auto")]
+    Auto,
+}
+
+#[napi(string_enum)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyslogProtocol {
+    #[napi(value = "unix")]
+    Unix,
+    #[napi(value = "udp")]
+    Udp,
+    #[napi(value = "tcp")]
+    Tcp,
+}
+
+#[napi(string_enum)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyslogFormat {
+    #[napi(value = "rfc5424")]
+    Rfc5424,
+    #[napi(value = "rfc3164")]
+    Rfc3164,
+}
+
+#[napi(string_enum)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RotationTimezone {
+    #[napi(value = "utc")]
+    Utc,
+    #[napi(value = "local")]
+    Local,
+}
+
+#[napi(string_enum)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FsyncPolicy {
+    #[napi(value = "never")]
+    Never,
+    #[napi(value = "everyBatch")]
+    EveryBatch,
+    #[napi(value = "interval")]
+    Interval,
+    #[napi(value = "onLevel")]
+    OnLevel,
+}
+
+#[napi(string_enum)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HttpBodyFormat {
+    #[napi(value = "json")]
+    Json,
+    #[napi(value = "ndjson")]
+    Ndjson,
+}
+
+#[napi(string_enum)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OtlpEncoding {
+    #[napi(value = "protobuf")]
+    Protobuf,
+    #[napi(value = "json")]
+    Json,
+}
+
+#[napi(string_enum)]
+#[derive(Debug, PartialEq, Eq)]
+pub enum RotateReason {
+    #[napi(value = "size")]
+    Size,
+    #[napi(value = "interval")]
+    Interval,
+    #[napi(value = "hourly")]
+    Hourly,
+    #[napi(value = "daily")]
+    Daily,
+    #[napi(value = "weekly")]
+    Weekly,
+    #[napi(value = "manual")]
+    Manual,
+}
+
+// Passed to the `onRotate()` callback. `old_path` is where the finished file
+// is now, e.g. `app.log.1` after size rotation.
+#[napi(object)]
+#[derive(Debug)]
+pub struct RotateEvent {
+    pub old_path: String,
+    pub new_path: String,
+    pub reason: RotateReason,
 }
 
 #[derive(Debug)]
 pub enum WorkerMsg {
     Entry(LogEntry),
+    // Writes buffered entries, then acknowledges.
+    Flush(SyncSender<()>),
     Shutdown,
 }
 
-#[napi(object)]
+#[derive(Serialize, Debug, Clone)]
+pub struct CallerLocation {
+    pub file: String,
+    pub line: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+}
+
 #[derive(Serialize, Debug)]
 pub struct LogEntry {
-    pub level: LogLevel,
+    pub level: Level,
     pub msg: Value,
     pub time: i64,
     pub pid: u32,
+    pub thread_id: u32,
+    pub thread_name: Option<String>,
+    pub caller: Option<CallerLocation>,
+    pub context: Option<Map<String, Value>>,
 }
 
 #[napi(object)]
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct MaskingConfig {
     pub keyword: Option<String>,
     pub exact: Option<Vec<String>>,
@@ -53,28 +185,139 @@ pub struct MaskingConfig {
 }
 
 #[napi(object)]
-#[derive(Clone)]
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColorTheme {
+    pub trace: Option<String>,
+    pub debug: Option<String>,
+    pub info: Option<String>,
+    pub warn: Option<String>,
+    pub error: Option<String>,
+    pub fatal: Option<String>,
+    pub time: Option<String>,
+    pub scope: Option<String>,
+    pub key: Option<String>,
+    pub value: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyslogConfig {
+    pub protocol: Option<SyslogProtocol>,
+    pub address: Option<String>,
+    pub format: Option<SyslogFormat>,
+    pub facility: Option<String>,
+    pub app_name: Option<String>,
+    pub msgid: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpConfig {
+    pub url: String,
+    pub headers: Option<HashMap<String, String>>,
+    pub body_format: Option<HttpBodyFormat>,
+    pub gzip: Option<bool>,
+    pub timeout_ms: Option<i64>,
+    pub max_retries: Option<u32>,
+    pub retry_base_ms: Option<i64>,
+    pub retry_max_ms: Option<i64>,
+    pub max_pending_retries: Option<u32>,
+}
+
+#[napi(object)]
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamConfig {
+    pub address: String,
+    pub tls: Option<bool>,
+    pub tls_server_name: Option<String>,
+    pub tls_ca_file: Option<String>,
+    pub connect_timeout_ms: Option<i64>,
+    pub reconnect_base_ms: Option<i64>,
+    pub reconnect_max_ms: Option<i64>,
+    pub spill_buffer_size: Option<u32>,
+}
+
+#[napi(object)]
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournaldConfig {
+    pub identifier: Option<String>,
+    pub socket_path: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtlpConfig {
+    pub endpoint: Option<String>,
+    pub encoding: Option<OtlpEncoding>,
+    pub headers: Option<HashMap<String, String>>,
+    pub gzip: Option<bool>,
+    pub timeout_ms: Option<i64>,
+    pub max_retries: Option<u32>,
+    pub resource_attributes: Option<HashMap<String, String>>,
+}
+
+#[napi(object)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct OutputConfig {
-    pub color: Option<bool>,
+    #[serde(
+        default,
+        deserialize_with = "crate::loader::color_setting",
+        serialize_with = "crate::loader::serialize_color"
+    )]
+    pub color: Option<Either<bool, ColorMode>>,
+    pub theme: Option<ColorTheme>,
     pub format: OutputFormat,
     pub target: OutputTarget,
     pub file_path: Option<String>,
     pub max_file_size: Option<i64>,
     pub max_backups: Option<u8>,
+    pub max_age_days: Option<u32>,
+    pub max_total_size: Option<i64>,
     pub rotate_daily: Option<bool>,
+    pub rotate_hourly: Option<bool>,
+    pub rotate_weekly: Option<bool>,
+    pub rotate_every_minutes: Option<u32>,
+    pub rotation_timezone: Option<RotationTimezone>,
+    pub file_pattern: Option<String>,
+    pub reopen_signal: Option<bool>,
+    pub file_mode: Option<u32>,
+    pub dir_mode: Option<u32>,
+    pub current_link: Option<bool>,
+    pub fsync: Option<FsyncPolicy>,
+    pub fsync_interval_ms: Option<i64>,
+    pub fsync_level: Option<String>,
     pub batch_enabled: Option<bool>,
     pub batch_size: Option<i64>,
     pub batch_interval_ms: Option<i64>,
     pub masking: Option<MaskingConfig>,
+    pub syslog: Option<SyslogConfig>,
+    pub http: Option<HttpConfig>,
+    pub stream: Option<StreamConfig>,
+    pub journald: Option<JournaldConfig>,
+    pub otlp: Option<OtlpConfig>,
+    pub fallback: Option<Vec<OutputTarget>>,
+    pub fallback_cooldown_ms: Option<i64>,
 }
 
 #[napi(object)]
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct FieldsConfig {
     pub pid: Option<bool>,
     pub time: Option<bool>,
     pub msg: Option<bool>,
     pub level: Option<bool>,
+    pub hostname: Option<bool>,
+    pub thread_id: Option<bool>,
+    pub thread_name: Option<bool>,
+    pub caller: Option<bool>,
 }
 
 impl Default for FieldsConfig {
@@ -84,28 +327,108 @@ impl Default for FieldsConfig {
             time: Some(true),
             msg: Some(true),
             level: Some(true),
+            hostname: Some(false),
+            thread_id: Some(false),
+            thread_name: Some(false),
+            caller: Some(false),
         }
     }
 }
 
 #[napi(object)]
-#[derive(Clone)]
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaseConfig {
+    pub service: Option<String>,
+    pub version: Option<String>,
+    pub env: Option<String>,
+    pub region: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextConfig {
+    pub open_telemetry: Option<bool>,
+}
+
+#[napi(object)]
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SamplingConfig {
+    pub sample: Option<HashMap<String, f64>>,
+    pub scopes: Option<HashMap<String, f64>>,
+    pub max_per_second: Option<u32>,
+    pub dedupe_window_ms: Option<i64>,
+}
+
+#[napi(object)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomLevel {
+    pub name: String,
+    pub severity: u32,
+    pub color: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct EnvConfig {
     pub output: OutputConfig,
     pub fields: Option<FieldsConfig>,
+    pub base: Option<BaseConfig>,
+    pub level: Option<String>,
+    pub levels: Option<String>,
+    pub debug_signal: Option<bool>,
+    pub custom_levels: Option<Vec<CustomLevel>>,
+    pub context: Option<ContextConfig>,
+    pub sampling: Option<SamplingConfig>,
 }
 
 #[napi(object)]
-#[derive(Clone)]
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct LoggerConfig {
     pub dev: Option<EnvConfig>,
     pub prod: Option<EnvConfig>,
+    // Named environments shaped like `EnvConfig`, plus an optional `extends`.
+    pub environments: Option<HashMap<String, Value>>,
+    pub env: Option<String>,
+    pub env_var: Option<String>,
+}
+
+// A config problem at `path`, e.g. `prod.output.batchSize`.
+#[napi(object)]
+#[derive(Clone, Debug)]
+pub struct ConfigIssue {
+    pub path: String,
+    pub message: String,
+}
+
+impl ConfigIssue {
+    pub fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        ConfigIssue {
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+// Errors make a config invalid; warnings flag settings that have no effect.
+#[napi(object)]
+#[derive(Clone, Debug, Default)]
+pub struct ValidationResult {
+    pub valid: bool,
+    pub errors: Vec<ConfigIssue>,
+    pub warnings: Vec<ConfigIssue>,
 }
 
 #[derive(Serialize)]
-pub struct SerializableLogEntry {
+#[serde(rename_all = "camelCase")]
+pub struct SerializableLogEntry<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub level: Option<LogLevel>,
+    pub level: Option<Level>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub msg: Option<Value>,
@@ -116,6 +439,33 @@ pub struct SerializableLogEntry {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pid: Option<u32>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<&'a str>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub caller: Option<CallerLocation>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<&'a str>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<&'a str>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<&'a str>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<&'a str>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub scope: Option<String>,
+
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub context: Option<Map<String, Value>>,
 }
@@ -1,15 +1,29 @@
-use chrono::Utc;
 use serde_json::{Map, Value};
-use std::fs;
-use std::io::Write;
-use std::path::{Path, PathBuf};
 use std::sync::{mpsc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 
-use crate::config::{BATCH_THREAD, LOGGER_CONFIG, MASKING_RULES, SENDER};
-use crate::format::{format_log_json, format_log_text};
-use crate::types::{EnvConfig, LogEntry, OutputFormat, OutputTarget, WorkerMsg};
+use crate::base::BaseFields;
+use crate::config::{BASE_FIELDS, BATCH_THREAD, LOGGER_CONFIG, MASKING_RULES, PALETTE, SENDER};
+use crate::fallback;
+use crate::file::{file_output, rotate_if_due, sync_if_due};
+use crate::format::{format_log_json, format_log_logfmt, format_log_text, format_log_text_line};
+use crate::http::{http_output, retry_pending_http};
+use crate::journald::journald_output;
+use crate::logger::sampler_summaries;
+use crate::otlp::otlp_output;
+use crate::rotation::Period;
+use crate::stream::{retry_pending_stream, stream_output, StreamTarget};
+use crate::syslog::syslog_output;
+use crate::theme::Palette;
+use crate::types::{EnvConfig, FsyncPolicy, LogEntry, OutputFormat, OutputTarget, WorkerMsg};
+
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Reads the scope without copying the message, for filtering before an entry is built.
+pub fn scope_of(val: &Value) -> Option<&str> {
+    val.get("scope").and_then(Value::as_str)
+}
 
 pub fn extract_scope_and_value(val: &Value) -> (Option<String>, Value) {
     match val {
@@ -26,8 +40,17 @@ pub fn extract_scope_and_value(val: &Value) -> (Option<String>, Value) {
     }
 }
 
-pub fn extract_scope_and_text(val: &Value) -> (Option<String>, String) {
+// Objects are pretty-printed over several lines unless `single_line` is set.
+pub fn extract_scope_and_text(val: &Value, single_line: bool) -> (Option<String>, String) {
     let (scope, msg_without_scope) = extract_scope_and_value(val);
+    let to_json = |value: &Value| {
+        let json = if single_line {
+            serde_json::to_string(value)
+        } else {
+            serde_json::to_string_pretty(value)
+        };
+        json.unwrap_or_else(|_| "<Invalid JSON>".into())
+    };
 
     let text = match &msg_without_scope {
         Value::String(s) => s.clone(),
@@ -37,35 +60,72 @@ pub fn extract_scope_and_text(val: &Value) -> (Option<String>, String) {
                 map.get("message")
                     .and_then(|v| v.as_str())
                     .map(|s| s.to_string())
-                    .unwrap_or_else(|| {
-                        serde_json::to_string_pretty(&msg_without_scope)
-                            .unwrap_or_else(|_| "<Invalid JSON>".into())
-                    })
+                    .unwrap_or_else(|| to_json(&msg_without_scope))
             } else {
-                serde_json::to_string_pretty(&msg_without_scope)
-                    .unwrap_or_else(|_| "<Invalid JSON>".into())
+                to_json(&msg_without_scope)
             }
         }
 
-        other => serde_json::to_string_pretty(other).unwrap_or_else(|_| "<Invalid JSON>".into()),
+        other => to_json(other),
     };
 
     (scope, text)
 }
 
+// How long shutdown() may spend delivering HTTP batches queued for retry and
+// the stream spill buffer. Whatever is still pending after that is dropped.
+const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+fn drain_pending() {
+    let deadline = Instant::now() + SHUTDOWN_DRAIN_TIMEOUT;
+    retry_pending_http(Some(deadline));
+    retry_pending_stream(Some(deadline));
+}
+
 pub fn init_batching_logger(config: &EnvConfig) {
-    if !config.output.batch_enabled.unwrap_or(false) {
+    // Network targets always batch: one request per log line would be far too
+    // slow, and connecting or writing to a stalled peer must not block the
+    // logging thread.
+    let is_network = matches!(
+        config.output.target,
+        OutputTarget::Http
+            | OutputTarget::Otlp
+            | OutputTarget::Tcp
+            | OutputTarget::UnixSocket
+            | OutputTarget::Syslog
+    );
+    // Scheduled rotation and interval syncs need the writer thread's timer to
+    // happen on time.
+    let uses_file = config.output.target == OutputTarget::File
+        || config
+            .output
+            .fallback
+            .iter()
+            .flatten()
+            .any(|target| *target == OutputTarget::File);
+    let needs_timer = uses_file
+        && (Period::of(&config.output).is_some()
+            || config.output.fsync == Some(FsyncPolicy::Interval));
+    // So do sampling summaries, which are otherwise only written by the next
+    // admitted entry.
+    let summarizes = config.sampling.as_ref().is_some_and(|sampling| {
+        sampling.max_per_second.is_some() || sampling.dedupe_window_ms.is_some()
+    });
+    if !config.output.batch_enabled.unwrap_or(false) && !is_network && !needs_timer && !summarizes {
         return;
     }
 
     let sender_mutex = SENDER.get_or_init(|| Mutex::new(None));
-    
-    if sender_mutex.lock().expect("Sender mutex poisoned").is_some() {
+
+    // Hold the lock until the worker is registered so that concurrent
+    // setConfig() calls from several worker threads spawn only one batch thread.
+    let mut sender_slot = sender_mutex.lock().expect("Sender mutex poisoned");
+    if sender_slot.is_some() {
         return;
     }
 
     let (tx, rx) = mpsc::channel::<WorkerMsg>();
-    
+
     let flush_interval_ms = config.output.batch_interval_ms.unwrap_or(100);
     let flush_interval_ms = flush_interval_ms.max(1) as u64;
 
@@ -74,27 +134,26 @@ pub fn init_batching_logger(config: &EnvConfig) {
 
     let handle = thread::spawn(move || {
         let mut buffer: Vec<LogEntry> = Vec::with_capacity(batch_size);
-        let mut last_flush = Instant::now();
+        let interval = Duration::from_millis(flush_interval_ms);
+        let mut last_tick = Instant::now();
 
         loop {
-            let elapsed = last_flush.elapsed();
-            let timeout = Duration::from_millis(flush_interval_ms).saturating_sub(elapsed);
+            let timeout = interval.saturating_sub(last_tick.elapsed());
 
             match rx.recv_timeout(timeout) {
                 Ok(WorkerMsg::Entry(entry)) => {
                     buffer.push(entry);
 
-                    if buffer.len() >= batch_size {
-                        flush(&mut buffer);
-                        last_flush = Instant::now();
-                        continue;
-                    }
-
                     while buffer.len() < batch_size {
                         match rx.try_recv() {
                             Ok(WorkerMsg::Entry(entry2)) => buffer.push(entry2),
+                            Ok(WorkerMsg::Flush(done)) => {
+                                flush(&mut buffer);
+                                let _ = done.send(());
+                            }
                             Ok(WorkerMsg::Shutdown) => {
                                 flush(&mut buffer);
+                                drain_pending();
                                 return;
                             }
                             Err(_) => break,
@@ -103,40 +162,65 @@ pub fn init_batching_logger(config: &EnvConfig) {
 
                     if buffer.len() >= batch_size {
                         flush(&mut buffer);
-                        last_flush = Instant::now();
                     }
                 }
 
+                Ok(WorkerMsg::Flush(done)) => {
+                    flush(&mut buffer);
+                    let _ = done.send(());
+                }
+
                 Ok(WorkerMsg::Shutdown) => {
                     if !buffer.is_empty() {
                         flush(&mut buffer);
                     }
+                    drain_pending();
                     break;
                 }
 
-                Err(mpsc::RecvTimeoutError::Timeout) => {
-                    if !buffer.is_empty() {
-                        flush(&mut buffer);
-                    }
-                    last_flush = Instant::now();
-                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
 
                 Err(mpsc::RecvTimeoutError::Disconnected) => {
                     if !buffer.is_empty() {
                         flush(&mut buffer);
                     }
+                    drain_pending();
                     break;
                 }
             }
+
+            // Checked after every message, not only on timeout: a steady
+            // stream of entries would otherwise hold off rotation, interval
+            // syncs and retries indefinitely.
+            if last_tick.elapsed() >= interval {
+                tick(&mut buffer);
+                last_tick = Instant::now();
+            }
         }
     });
 
-    *sender_mutex.lock().expect("Sender mutex poisoned") = Some(tx);
-    
+    *sender_slot = Some(tx);
+
     let thread_mutex = BATCH_THREAD.get_or_init(|| Mutex::new(None));
     *thread_mutex.lock().expect("Batch thread mutex poisoned") = Some(handle);
 }
 
+// Waits until the batch thread has written everything queued before the call.
+pub fn flush_batch() {
+    let Some(sender_mutex) = SENDER.get() else {
+        return;
+    };
+    let (done_tx, done_rx) = mpsc::sync_channel(1);
+    let sent = sender_mutex
+        .lock()
+        .expect("Sender mutex poisoned")
+        .as_ref()
+        .is_some_and(|sender| sender.send(WorkerMsg::Flush(done_tx)).is_ok());
+    if sent {
+        let _ = done_rx.recv_timeout(FLUSH_TIMEOUT);
+    }
+}
+
 fn flush(buf: &mut Vec<LogEntry>) {
     if buf.is_empty() {
         return;
@@ -147,60 +231,40 @@ fn flush(buf: &mut Vec<LogEntry>) {
         return;
     };
 
-    let cfg: EnvConfig = cfg_cell.read().expect("Logger config lock poisoned").clone();
+    let cfg: EnvConfig = cfg_cell
+        .read()
+        .expect("Logger config lock poisoned")
+        .clone();
 
-    for entry in buf.drain(..) {
-        match cfg.output.format {
-            OutputFormat::Text => format_log_text(&entry, &cfg),
-            OutputFormat::Json => format_log_json(&entry, &cfg),
-        }
-    }
+    write_entries(&cfg, &std::mem::take(buf));
 }
 
-pub fn cleanup_old_daily_logs(base_path: &str, max_backups: u8) {
-    let base = Path::new(base_path);
-    let parent = base.parent().unwrap_or_else(|| Path::new("."));
-    let stem = base.file_stem().and_then(|s| s.to_str()).unwrap_or("log");
-    let ext = base.extension().and_then(|s| s.to_str()).unwrap_or("log");
-
-    let pattern_prefix = format!("{}_", stem);
-    let pattern_suffix = format!(".{}", ext);
-
-    let mut files: Vec<PathBuf> = match fs::read_dir(parent) {
-        Ok(entries) => entries
-            .filter_map(|entry| {
-                let entry = entry.ok()?;
-                let path = entry.path();
-                let fname = path.file_name()?.to_str()?;
-
-                if fname.starts_with(&pattern_prefix) && fname.ends_with(&pattern_suffix) {
-                    Some(path)
-                } else {
-                    None
-                }
-            })
-            .collect(),
-        Err(_) => return,
-    };
-
-    files.sort();
-
-    while files.len() > max_backups as usize {
-        let old_path = files.remove(0);
-        let _ = fs::remove_file(old_path);
-    }
+// Work due once per batch interval: sampling summaries, the partial batch,
+// scheduled rotation and syncs, and network retries.
+fn tick(buffer: &mut Vec<LogEntry>) {
+    buffer.extend(sampler_summaries());
+    flush(buffer);
+    tick_file();
+    retry_pending_http(None);
+    retry_pending_stream(None);
 }
 
-pub fn should_rotate(path: &str, config: &EnvConfig) -> bool {
-    let max_size = config.output.max_file_size.unwrap_or(10 * 1024 * 1024); // 10 MB
+// Timed work for the file sink that must not wait for the next entry.
+fn tick_file() {
+    let Some(cfg_cell) = LOGGER_CONFIG.get() else {
+        return;
+    };
+    let cfg = cfg_cell.read().expect("Logger config lock poisoned");
+    rotate_if_due(&cfg.output);
+    sync_if_due(&cfg.output);
+}
 
-    if let Ok(metadata) = std::fs::metadata(path) {
-        if max_size >= 0 {
-            return metadata.len() >= max_size as u64;
-        }
+pub fn format_entry(entry: &LogEntry, config: &EnvConfig) -> String {
+    match config.output.format {
+        OutputFormat::Text => format_log_text(entry, config),
+        OutputFormat::Json => format_log_json(entry, config),
+        OutputFormat::Logfmt => format_log_logfmt(entry, config),
     }
-
-    false
 }
 
 pub fn mask_message_if_needed(msg: &Value) -> Value {
@@ -212,90 +276,143 @@ pub fn mask_message_if_needed(msg: &Value) -> Value {
     }
 }
 
-pub fn validate_config(env_config: &EnvConfig) -> Result<(), String> {
-    let output = &env_config.output;
-
-    if matches!(output.target, OutputTarget::File)
-        && (output.file_path.is_none() || output.file_path.as_ref().unwrap().is_empty())
-    {
-        return Err("LoggerConfig.output.filePath must be set when using File target.".to_string());
+// Runs `f` under the read lock instead of cloning the fields for every entry.
+pub fn with_base_fields<R>(f: impl FnOnce(&BaseFields) -> R) -> R {
+    match BASE_FIELDS.get() {
+        Some(base_cell) => f(&base_cell.read().expect("Base fields lock poisoned")),
+        None => f(&BaseFields::default()),
     }
-
-    Ok(())
 }
 
-pub fn write_output(config: &EnvConfig, message: &str) {
-    match config.output.target {
-        OutputTarget::Stdout => println!("{}", message),
-        OutputTarget::Stderr => eprintln!("{}", message),
-        OutputTarget::File => {
-            file_output(config, message);
-        }
-        OutputTarget::Null => { /* do nothing */ }
-    }
+pub fn current_palette() -> Option<Palette> {
+    PALETTE
+        .get()
+        .and_then(|palette_cell| palette_cell.read().expect("Palette lock poisoned").clone())
 }
 
-fn file_output(config: &EnvConfig, message: &str) {
-    if let Some(base_path) = &config.output.file_path {
-        let path = if config.output.rotate_daily.unwrap_or(false) {
-            cleanup_old_daily_logs(base_path, config.output.max_backups.unwrap_or(7));
-
-            let date_str = Utc::now().format("%Y-%m-%d").to_string();
-            let extension = std::path::Path::new(base_path)
-                .extension()
-                .and_then(|ext| ext.to_str())
-                .unwrap_or("log");
-
-            let stem = std::path::Path::new(base_path)
-                .file_stem()
-                .and_then(|stem| stem.to_str())
-                .unwrap_or("log");
+// Writes entries to the configured target, then down the fallback chain.
+// With a fallback configured, a failing sink is skipped for a cool-down period
+// (its circuit is open) and entries go to the next sink that accepts them.
+pub fn write_entries(config: &EnvConfig, entries: &[LogEntry]) {
+    if entries.is_empty() {
+        return;
+    }
 
-            format!("{}_{}.{}", stem, date_str, extension)
-        } else {
-            base_path.clone()
-        };
+    let lines: Vec<String> = entries
+        .iter()
+        .map(|entry| format_entry(entry, config))
+        .collect();
+
+    let fallback = config.output.fallback.clone().unwrap_or_default();
+    let cooldown = (!fallback.is_empty()).then(|| {
+        let ms = config
+            .output
+            .fallback_cooldown_ms
+            .unwrap_or(fallback::DEFAULT_COOLDOWN_MS);
+        Duration::from_millis(ms.max(0) as u64)
+    });
 
-        let rotate = should_rotate(&path, &config);
-        if rotate && !config.output.rotate_daily.unwrap_or(false) {
-            rotate_logs(&path, &config);
+    let mut written = 0;
+    let mut last_error = None;
+    for target in std::iter::once(config.output.target).chain(fallback) {
+        if cooldown.is_some() && fallback::is_open(target) {
+            continue;
         }
 
-        if let Ok(mut file) = std::fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&path)
-        {
-            if let Err(err) = writeln!(file, "{}", message) {
-                eprintln!(
-                    "[Logger] Failed to write to file {}: {}. Fallback to stderr.",
-                    path, err
-                );
-                eprintln!("{}", message);
+        match write_target(
+            target,
+            config,
+            &entries[written..],
+            &lines[written..],
+            cooldown.is_none(),
+        ) {
+            Ok(()) => {
+                if cooldown.is_some() {
+                    fallback::record_success(target);
+                }
+                return;
+            }
+            Err((done, reason)) => {
+                written += done;
+                fallback::record_failure(target, &reason, cooldown);
+                last_error = Some((target, reason));
             }
-        } else {
-            eprintln!(
-                "[Logger] Failed to open log file: {}. Fallback to stderr.",
-                path
-            );
-            eprintln!("{}", message);
         }
-    } else {
-        eprintln!("[Logger] No file path provided for log output.");
     }
-}
-
-fn rotate_logs(path: &str, config: &EnvConfig) {
-    let max_backups = config.output.max_backups.unwrap_or(3);
 
-    for i in (1..=max_backups).rev() {
-        let src = format!("{}.{}", path, i - 1);
-        let dst = format!("{}.{}", path, i);
+    match last_error {
+        Some((target, reason)) => eprintln!(
+            "[Logger] Output {} failed: {}. Fallback to stderr.",
+            fallback::target_name(target),
+            reason
+        ),
+        None => eprintln!("[Logger] All output targets are unavailable. Fallback to stderr."),
+    }
+    for line in &lines[written..] {
+        eprintln!("{}", line);
+    }
+}
 
-        let src_actual = if i == 1 { path.to_string() } else { src };
+// On failure, returns how many leading entries were written before it and why.
+// `standalone` means there is no fallback, so sinks may buffer or retry on their own.
+fn write_target(
+    target: OutputTarget,
+    config: &EnvConfig,
+    entries: &[LogEntry],
+    lines: &[String],
+    standalone: bool,
+) -> Result<(), (usize, String)> {
+    let per_line = |write: &dyn Fn(&LogEntry, &str) -> Result<(), String>| {
+        for (done, (entry, line)) in entries.iter().zip(lines).enumerate() {
+            write(entry, line).map_err(|reason| (done, reason))?;
+        }
+        Ok(())
+    };
 
-        if std::path::Path::new(&src_actual).exists() {
-            let _ = std::fs::rename(src_actual, dst);
+    match target {
+        OutputTarget::Stdout => per_line(&|_, line| {
+            println!("{}", line);
+            Ok(())
+        }),
+        OutputTarget::Stderr => per_line(&|_, line| {
+            eprintln!("{}", line);
+            Ok(())
+        }),
+        OutputTarget::File => file_output(&config.output, entries, lines),
+        OutputTarget::Null => Ok(()),
+        OutputTarget::Syslog => {
+            let syslog = config.output.syslog.clone().unwrap_or_default();
+            per_line(&|entry, line| syslog_output(&syslog, entry, line))
+        }
+        OutputTarget::Http => {
+            let http = config.output.http.clone().unwrap_or_default();
+            http_output(&http, config.output.format, lines, standalone).map_err(|e| (0, e))
+        }
+        OutputTarget::Tcp | OutputTarget::UnixSocket => {
+            // Collectors split the stream on newlines, so text entries are
+            // rendered again on a single line.
+            let single_lines: Vec<String>;
+            let lines = match config.output.format {
+                OutputFormat::Text => {
+                    single_lines = entries
+                        .iter()
+                        .map(|entry| format_log_text_line(entry, config))
+                        .collect();
+                    &single_lines
+                }
+                OutputFormat::Json | OutputFormat::Logfmt => lines,
+            };
+            let stream = config.output.stream.clone().unwrap_or_default();
+            let kind = StreamTarget::from_output(&target).unwrap_or(StreamTarget::Tcp);
+            stream_output(kind, &stream, lines, standalone).map_err(|e| (0, e))
+        }
+        OutputTarget::Journald => {
+            let journald = config.output.journald.clone().unwrap_or_default();
+            per_line(&|entry, _| journald_output(&journald, entry))
+        }
+        OutputTarget::Otlp => {
+            let otlp = config.output.otlp.clone().unwrap_or_default();
+            otlp_output(&otlp, entries, standalone).map_err(|e| (0, e))
         }
     }
 }
@@ -0,0 +1,443 @@
+use napi::{Env, JsObject, Status};
+use std::fmt;
+use std::path::Path;
+
+use crate::directives::LevelFilter;
+use crate::environments;
+use crate::levels::{LevelRegistry, BUILTIN_LEVELS};
+use crate::loader::apply_env_overrides;
+use crate::rotation::{validate_pattern, validate_pattern_period, Period};
+use crate::stream::StreamTarget;
+use crate::syslog::facility_code;
+use crate::theme::Style;
+use crate::types::{
+    ColorTheme, ConfigIssue, EnvConfig, LoggerConfig, OutputConfig, OutputTarget, ValidationResult,
+};
+
+// The `code` of the error thrown for an invalid config.
+pub const INVALID_CONFIG: &str = "ERR_INVALID_CONFIG";
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+// Selects the environment, applies EVENTUM_OUTPUT_* overrides and validates the
+// result, as `setConfig()` would. The config is only returned when it is valid.
+pub fn check(config: LoggerConfig) -> (Option<EnvConfig>, ValidationResult) {
+    let mut validator = Validator::default();
+    let env_config = match environments::select(config) {
+        Ok((section, mut env_config)) => {
+            validator.errors = apply_env_overrides(&mut env_config.output);
+            // Issues in an overridden config could come from either source, so
+            // the variables are fixed first.
+            if validator.errors.is_empty() {
+                validator.section = section;
+                validator.env_config(&env_config);
+            }
+            Some(env_config)
+        }
+        Err(issue) => {
+            validator.errors.push(issue);
+            None
+        }
+    };
+
+    let valid = validator.errors.is_empty();
+    let result = ValidationResult {
+        valid,
+        errors: validator.errors,
+        warnings: validator.warnings,
+    };
+    (env_config.filter(|_| valid), result)
+}
+
+// Throws an `Error` with `code`, `errors` and `warnings`, which the JS wrappers
+// turn into a `ConfigValidationError`.
+pub fn invalid_config(env: Env, result: ValidationResult) -> napi::Error {
+    let lines: Vec<String> = result
+        .errors
+        .iter()
+        .map(|issue| format!("  {}", issue))
+        .collect();
+    let message = format!("Invalid logger config:\n{}", lines.join("\n"));
+
+    let thrown = env
+        .create_error(napi::Error::new(Status::InvalidArg, message.clone()))
+        .and_then(|mut error: JsObject| {
+            error.set_named_property("code", INVALID_CONFIG)?;
+            error.set_named_property("errors", result.errors)?;
+            error.set_named_property("warnings", result.warnings)?;
+            env.throw(error)
+        });
+    match thrown {
+        // Returning a pending exception leaves the thrown error in place.
+        Ok(()) => napi::Error::new(Status::PendingException, message),
+        Err(err) => err,
+    }
+}
+
+#[derive(Default)]
+struct Validator {
+    // Path of the selected environment, e.g. `prod` or `environments.staging`.
+    section: String,
+    errors: Vec<ConfigIssue>,
+    warnings: Vec<ConfigIssue>,
+}
+
+impl Validator {
+    fn path(&self, field: &str) -> String {
+        format!("{}.{}", self.section, field)
+    }
+
+    fn error(&mut self, field: &str, message: impl Into<String>) {
+        let path = self.path(field);
+        self.errors.push(ConfigIssue::new(path, message));
+    }
+
+    fn warn(&mut self, field: &str, message: impl Into<String>) {
+        let path = self.path(field);
+        self.warnings.push(ConfigIssue::new(path, message));
+    }
+
+    fn positive(&mut self, field: &str, value: Option<i64>) {
+        if value.is_some_and(|value| value <= 0) {
+            self.error(field, "must be greater than 0");
+        }
+    }
+
+    // A retry or reconnect delay range.
+    fn backoff(&mut self, section: &str, base: (&str, Option<i64>), max: (&str, Option<i64>)) {
+        self.positive(&format!("{}.{}", section, base.0), base.1);
+        self.positive(&format!("{}.{}", section, max.0), max.1);
+        if let (Some(base_ms), Some(max_ms)) = (base.1, max.1) {
+            if max_ms < base_ms {
+                self.error(
+                    &format!("{}.{}", section, max.0),
+                    format!("must not be less than {}", base.0),
+                );
+            }
+        }
+    }
+
+    fn env_config(&mut self, env_config: &EnvConfig) {
+        self.output(&env_config.output);
+        self.custom_levels(env_config);
+
+        let levels = LevelRegistry::from(env_config);
+        if let Some(level) = &env_config.level {
+            if levels.lookup(level).is_none() {
+                self.error("level", format!("'{}' is not a known log level", level));
+            }
+        }
+        if let Some(level) = &env_config.output.fsync_level {
+            if levels.lookup(level).is_none() {
+                self.error(
+                    "output.fsyncLevel",
+                    format!("'{}' is not a known log level", level),
+                );
+            }
+        }
+        if let Some(spec) = &env_config.levels {
+            if let Err(e) = LevelFilter::parse(spec, None, &levels) {
+                self.error("levels", e);
+            }
+        }
+
+        if let Some(sampling) = &env_config.sampling {
+            for (name, rate) in sampling.sample.iter().flatten() {
+                let field = format!("sampling.sample.{}", name);
+                if levels.lookup(name).is_none() {
+                    self.error(&field, format!("'{}' is not a known log level", name));
+                }
+                if !(0.0..=1.0).contains(rate) {
+                    self.error(&field, "must be between 0 and 1");
+                }
+            }
+            for (pattern, rate) in sampling.scopes.iter().flatten() {
+                if !(0.0..=1.0).contains(rate) {
+                    self.error(
+                        &format!("sampling.scopes.{}", pattern),
+                        "must be between 0 and 1",
+                    );
+                }
+            }
+            if sampling.max_per_second == Some(0) {
+                self.error("sampling.maxPerSecond", "must be greater than 0");
+            }
+            self.positive("sampling.dedupeWindowMs", sampling.dedupe_window_ms);
+        }
+    }
+
+    fn custom_levels(&mut self, env_config: &EnvConfig) {
+        let mut seen: Vec<String> = Vec::new();
+        for (i, custom) in env_config.custom_levels.iter().flatten().enumerate() {
+            let field = format!("customLevels[{}]", i);
+            let name = custom.name.trim().to_lowercase();
+            if name.is_empty() {
+                self.error(&format!("{}.name", field), "must not be empty");
+            } else if BUILTIN_LEVELS
+                .iter()
+                .any(|level| level.name().eq_ignore_ascii_case(&name))
+            {
+                self.error(
+                    &format!("{}.name", field),
+                    format!("'{}' conflicts with a built-in level", custom.name),
+                );
+            } else if seen.contains(&name) {
+                self.error(
+                    &format!("{}.name", field),
+                    format!("'{}' is defined more than once", custom.name),
+                );
+            }
+            if let Some(color) = &custom.color {
+                if let Err(e) = color.parse::<Style>() {
+                    self.error(&format!("{}.color", field), format!("is invalid: {}", e));
+                }
+            }
+            seen.push(name);
+        }
+    }
+
+    fn output(&mut self, output: &OutputConfig) {
+        let fallback = output.fallback.as_deref().unwrap_or_default();
+        for (i, target) in fallback.iter().enumerate() {
+            if *target == output.target || fallback[..i].contains(target) {
+                self.error(
+                    &format!("output.fallback[{}]", i),
+                    format!("repeats the {:?} target", target),
+                );
+            }
+        }
+
+        // Every sink in the chain needs its own settings, not just the primary one.
+        let mut targets: Vec<OutputTarget> = Vec::new();
+        for target in std::iter::once(output.target).chain(fallback.iter().copied()) {
+            if !targets.contains(&target) {
+                targets.push(target);
+                self.target(output, target);
+            }
+        }
+        self.unused(output, &targets);
+
+        for (key, spec) in output.theme.iter().flat_map(ColorTheme::styles) {
+            if let Err(e) = spec.as_deref().map(str::parse::<Style>).transpose() {
+                self.error(
+                    &format!("output.theme.{}", key),
+                    format!("is invalid: {}", e),
+                );
+            }
+        }
+
+        self.positive("output.maxFileSize", output.max_file_size);
+        if output.max_backups == Some(0) {
+            self.error("output.maxBackups", "must be at least 1");
+        }
+        self.positive("output.batchSize", output.batch_size);
+        self.positive("output.batchIntervalMs", output.batch_interval_ms);
+        if output.fallback_cooldown_ms.is_some_and(|ms| ms < 0) {
+            self.error("output.fallbackCooldownMs", "must not be negative");
+        }
+
+        if let Some(http) = &output.http {
+            self.positive("output.http.timeoutMs", http.timeout_ms);
+            self.backoff(
+                "output.http",
+                ("retryBaseMs", http.retry_base_ms),
+                ("retryMaxMs", http.retry_max_ms),
+            );
+        }
+        if let Some(stream) = &output.stream {
+            self.positive("output.stream.connectTimeoutMs", stream.connect_timeout_ms);
+            self.backoff(
+                "output.stream",
+                ("reconnectBaseMs", stream.reconnect_base_ms),
+                ("reconnectMaxMs", stream.reconnect_max_ms),
+            );
+        }
+        if let Some(otlp) = &output.otlp {
+            self.positive("output.otlp.timeoutMs", otlp.timeout_ms);
+            if let Some(endpoint) = &otlp.endpoint {
+                if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+                    self.error(
+                        "output.otlp.endpoint",
+                        format!("'{}' must be an http:// or https:// URL", endpoint),
+                    );
+                }
+            }
+        }
+        if let Some(facility) = output.syslog.as_ref().and_then(|s| s.facility.as_ref()) {
+            if facility_code(facility).is_none() {
+                self.error(
+                    "output.syslog.facility",
+                    format!("'{}' is not a known syslog facility", facility),
+                );
+            }
+        }
+    }
+
+    // Settings for a sink that is neither the target nor a fallback.
+    fn unused(&mut self, output: &OutputConfig, targets: &[OutputTarget]) {
+        let sections = [
+            ("filePath", output.file_path.is_some(), OutputTarget::File),
+            ("syslog", output.syslog.is_some(), OutputTarget::Syslog),
+            ("http", output.http.is_some(), OutputTarget::Http),
+            (
+                "journald",
+                output.journald.is_some(),
+                OutputTarget::Journald,
+            ),
+            ("otlp", output.otlp.is_some(), OutputTarget::Otlp),
+        ];
+        for (field, set, target) in sections {
+            if set && !targets.contains(&target) {
+                self.warn(
+                    &format!("output.{}", field),
+                    format!("is ignored without the {:?} target", target),
+                );
+            }
+        }
+        let streams = [OutputTarget::Tcp, OutputTarget::UnixSocket];
+        if output.stream.is_some() && !streams.iter().any(|target| targets.contains(target)) {
+            self.warn(
+                "output.stream",
+                "is ignored without the Tcp or UnixSocket target",
+            );
+        }
+    }
+
+    fn target(&mut self, output: &OutputConfig, target: OutputTarget) {
+        match target {
+            OutputTarget::File => self.file(output),
+            OutputTarget::Http if output.http.as_ref().is_none_or(|http| http.url.is_empty()) => {
+                self.error("output.http.url", "must be set when using the Http target");
+            }
+            _ => {}
+        }
+
+        if let Some(target) = StreamTarget::from_output(&target) {
+            match output.stream.as_ref().filter(|s| !s.address.is_empty()) {
+                None => self.error(
+                    "output.stream.address",
+                    "must be set when using the Tcp or UnixSocket target",
+                ),
+                Some(stream) if target == StreamTarget::Unix && stream.tls.unwrap_or(false) => {
+                    self.error("output.stream.tls", "is only supported with the Tcp target")
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    fn file(&mut self, output: &OutputConfig) {
+        match output.file_path.as_deref().filter(|path| !path.is_empty()) {
+            Some(path) => self.writable(path),
+            None => self.error("output.filePath", "must be set when using the File target"),
+        }
+
+        let schedules = [
+            output.rotate_every_minutes.is_some(),
+            output.rotate_hourly.unwrap_or(false),
+            output.rotate_daily.unwrap_or(false),
+            output.rotate_weekly.unwrap_or(false),
+        ];
+        if schedules.iter().filter(|set| **set).count() > 1 {
+            self.error(
+                "output",
+                "accepts only one of rotateEveryMinutes, rotateHourly, rotateDaily and rotateWeekly",
+            );
+        }
+        if output
+            .rotate_every_minutes
+            .is_some_and(|minutes| !(1..=1440).contains(&minutes))
+        {
+            self.error("output.rotateEveryMinutes", "must be between 1 and 1440");
+        }
+        if let Some(pattern) = &output.file_pattern {
+            let period = Period::of(output);
+            if period.is_none() {
+                self.error(
+                    "output.filePattern",
+                    "requires a time-based rotation schedule",
+                );
+            }
+            let result = validate_pattern(pattern).and_then(|()| match period {
+                Some(period) => validate_pattern_period(pattern, period),
+                None => Ok(()),
+            });
+            if let Err(e) = result {
+                self.error("output.filePattern", format!("'{}' {}", pattern, e));
+            }
+        }
+        if output.max_age_days == Some(0) {
+            self.error("output.maxAgeDays", "must be greater than 0");
+        }
+        self.positive("output.maxTotalSize", output.max_total_size);
+        if output.file_mode.is_some_and(|mode| mode > 0o7777) {
+            self.error("output.fileMode", "must be a permission mode such as 0o640");
+        }
+        if output.dir_mode.is_some_and(|mode| mode > 0o7777) {
+            self.error("output.dirMode", "must be a permission mode such as 0o750");
+        }
+        if output.current_link.unwrap_or(false) && Period::of(output).is_none() {
+            self.error(
+                "output.currentLink",
+                "requires a time-based rotation schedule",
+            );
+        }
+        self.positive("output.fsyncIntervalMs", output.fsync_interval_ms);
+    }
+
+    // The log file, or the directory it will be created in. Missing directories
+    // are created on the first write, so the nearest existing one is checked.
+    fn writable(&mut self, file_path: &str) {
+        let path = Path::new(file_path);
+        if path.is_dir() {
+            self.error("output.filePath", format!("'{}' is a directory", file_path));
+            return;
+        }
+        if path.exists() {
+            if !writable(path) {
+                self.error(
+                    "output.filePath",
+                    format!("'{}' is not writable", file_path),
+                );
+            }
+            return;
+        }
+
+        let dir = path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        let Some(existing) = dir.ancestors().find(|dir| dir.exists()) else {
+            return;
+        };
+        if !existing.is_dir() {
+            self.error(
+                "output.filePath",
+                format!("'{}' is not a directory", existing.display()),
+            );
+        } else if !writable(existing) {
+            self.error(
+                "output.filePath",
+                format!("directory '{}' is not writable", existing.display()),
+            );
+        }
+    }
+}
+
+#[cfg(unix)]
+fn writable(path: &Path) -> bool {
+    use std::os::unix::ffi::OsStrExt;
+    let Ok(path) = std::ffi::CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+    unsafe { libc::access(path.as_ptr(), libc::W_OK) == 0 }
+}
+
+#[cfg(not(unix))]
+fn writable(path: &Path) -> bool {
+    std::fs::metadata(path).is_ok_and(|metadata| !metadata.permissions().readonly())
+}
@@ -0,0 +1,141 @@
+import * as logger from '../index.js';
+import fs from 'fs';
+import os from 'os';
+
+const logFile = './test.base-fields.log';
+
+describe('Static Base Fields', () => {
+  beforeAll(() => {
+    const config = {
+      prod: {
+        output: {
+          color: false,
+          format: 1, // JSON
+          target: 2, // File
+          filePath: logFile
+        },
+        fields: {
+          time: true,
+          pid: false,
+          msg: true,
+          level: true,
+          hostname: true
+        },
+        base: {
+          service: 'checkout',
+          version: '1.4.2',
+          env: 'staging',
+          region: 'eu-west-1'
+        }
+      }
+    };
+
+    const ok = logger.setConfig(config);
+    if (!ok) {
+      throw new Error('Logger rejected config');
+    }
+  });
+
+  afterAll(() => {
+    try {
+      if (fs.existsSync(logFile)) {
+        fs.unlinkSync(logFile);
+      }
+    } catch (err) {
+      // Ignore cleanup errors
+    }
+  });
+
+  test('should emit base fields in every JSON entry', (done) => {
+    logger.info('First entry');
+    logger.warn({ scope: 'Payments', message: 'Second entry' });
+    logger.shutdown();
+
+    setTimeout(() => {
+      const output = fs.readFileSync(logFile, 'utf8');
+      const lines = output.trim().split('\n').map((line) => JSON.parse(line));
+
+      expect(lines.length).toBe(2);
+      for (const parsed of lines) {
+        expect(parsed.hostname).toBe(os.hostname());
+        expect(parsed.service).toBe('checkout');
+        expect(parsed.version).toBe('1.4.2');
+        expect(parsed.env).toBe('staging');
+        expect(parsed.region).toBe('eu-west-1');
+      }
+      expect(lines[1].scope).toBe('Payments');
+      done();
+    }, 500);
+  });
+
+  test('should render base fields in text output', (done) => {
+    fs.writeFileSync(logFile, '');
+
+    logger.setConfig({
+      prod: {
+        output: {
+          color: false,
+          format: 0, // Text
+          target: 2, // File
+          filePath: logFile
+        },
+        fields: {
+          time: false,
+          msg: true,
+          level: true
+        },
+        base: {
+          service: 'checkout',
+          env: 'staging'
+        }
+      }
+    });
+
+    logger.info('Text entry');
+    logger.shutdown();
+
+    setTimeout(() => {
+      const output = fs.readFileSync(logFile, 'utf8');
+
+      expect(output).toContain('[service=checkout env=staging]');
+      expect(output).not.toContain('host=');
+      done();
+    }, 500);
+  });
+
+  test('should render base fields in logfmt output', (done) => {
+    fs.writeFileSync(logFile, '');
+
+    logger.setConfig({
+      prod: {
+        output: {
+          format: 2, // Logfmt
+          target: 2, // File
+          filePath: logFile
+        },
+        fields: {
+          time: false,
+          msg: true,
+          level: true,
+          hostname: true
+        },
+        base: {
+          service: 'checkout',
+          region: 'eu west'
+        }
+      }
+    });
+
+    logger.info('Logfmt entry');
+    logger.shutdown();
+
+    setTimeout(() => {
+      const output = fs.readFileSync(logFile, 'utf8');
+
+      expect(output).toBe(
+        `level=Info hostname=${os.hostname()} service=checkout region="eu west" msg="Logfmt entry"\n`
+      );
+      done();
+    }, 500);
+  });
+});
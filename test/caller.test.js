@@ -0,0 +1,108 @@
+import * as logger from '../index.js';
+import native from '../index.js';
+import fs from 'fs';
+
+const logFile = './test.caller.log';
+
+function configure(format, caller) {
+  const ok = logger.setConfig({
+    prod: {
+      output: {
+        color: false,
+        format,
+        target: 2, // File
+        filePath: logFile
+      },
+      fields: {
+        time: false,
+        msg: true,
+        level: true,
+        caller
+      }
+    }
+  });
+  if (!ok) {
+    throw new Error('Logger rejected config');
+  }
+}
+
+function readLines() {
+  return fs.readFileSync(logFile, 'utf8').trim().split('\n').filter(Boolean);
+}
+
+describe('Caller Location', () => {
+  afterEach(() => {
+    try {
+      if (fs.existsSync(logFile)) {
+        fs.unlinkSync(logFile);
+      }
+    } catch (err) {
+      // Ignore cleanup errors
+    }
+  });
+
+  test('should include the call site in JSON output', () => {
+    configure(1, true);
+
+    function handleRequest() {
+      logger.info('Caller in JSON');
+    }
+    handleRequest();
+
+    const parsed = JSON.parse(readLines()[0]);
+    expect(parsed.caller).toBeDefined();
+    expect(parsed.caller.file).toContain('caller.test.js');
+    expect(parsed.caller.line).toBeGreaterThan(0);
+    expect(parsed.caller.function).toContain('handleRequest');
+  });
+
+  test('should render the call site in text output', () => {
+    configure(0, true);
+
+    logger.error('Caller in text');
+
+    const line = readLines()[0];
+    expect(line).toMatch(/\(\S*caller\.test\.js:\d+\)/);
+    expect(line).toContain('Caller in text');
+  });
+
+  test('should find the call site through logger.log', () => {
+    configure(1, true);
+
+    function audit() {
+      logger.log('warn', 'Caller through log');
+    }
+    audit();
+
+    const parsed = JSON.parse(readLines()[0]);
+    expect(parsed.caller.file).toContain('caller.test.js');
+    expect(parsed.caller.function).toContain('audit');
+  });
+
+  test('should find the call site through the default export', () => {
+    configure(1, true);
+
+    function handleDirect() {
+      native.info('Caller without wrapper');
+      native.log('error', 'Log without wrapper');
+    }
+    handleDirect();
+
+    const callers = readLines().map((line) => JSON.parse(line).caller);
+    expect(callers).toHaveLength(2);
+    for (const caller of callers) {
+      expect(caller.file).toContain('caller.test.js');
+      expect(caller.function).toContain('handleDirect');
+    }
+    expect(callers[1].line).toBe(callers[0].line + 1);
+  });
+
+  test('should omit the call site when disabled', () => {
+    configure(1, false);
+
+    logger.info('No caller');
+
+    const parsed = JSON.parse(readLines()[0]);
+    expect(parsed.caller).toBeUndefined();
+  });
+});
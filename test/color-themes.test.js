@@ -0,0 +1,110 @@
+import * as logger from '../index.js';
+import fs from 'fs';
+
+const logFile = './test.color-themes.log';
+const ANSI = /\u001b\[[0-9;]*m/;
+
+function configure(output) {
+  const ok = logger.setConfig({
+    prod: {
+      output: {
+        format: 0, // Text
+        target: 2, // File
+        filePath: logFile,
+        ...output
+      },
+      fields: {
+        time: true,
+        msg: true,
+        level: true
+      }
+    }
+  });
+  if (!ok) {
+    throw new Error('Logger rejected config');
+  }
+}
+
+function readOutput() {
+  return fs.readFileSync(logFile, 'utf8');
+}
+
+describe('Color Themes', () => {
+  const savedEnv = { NO_COLOR: process.env.NO_COLOR, FORCE_COLOR: process.env.FORCE_COLOR };
+
+  beforeEach(() => {
+    delete process.env.NO_COLOR;
+    delete process.env.FORCE_COLOR;
+  });
+
+  afterEach(() => {
+    try {
+      if (fs.existsSync(logFile)) {
+        fs.unlinkSync(logFile);
+      }
+    } catch (err) {
+      // Ignore cleanup errors
+    }
+  });
+
+  afterAll(() => {
+    for (const [key, value] of Object.entries(savedEnv)) {
+      if (value === undefined) {
+        delete process.env[key];
+      } else {
+        process.env[key] = value;
+      }
+    }
+  });
+
+  test('should not colorize non-TTY targets in auto mode', () => {
+    configure({ color: 'auto' });
+    logger.info({ scope: 'Auth', user: 'alice' });
+
+    expect(readOutput()).not.toMatch(ANSI);
+  });
+
+  test('should colorize level badge, timestamp and scope separately', () => {
+    configure({ color: true });
+    logger.warn({ scope: 'Auth', message: 'Colored' });
+
+    const output = readOutput();
+    expect(output).toContain('\u001b[33m[Warn]\u001b[0m');
+    expect(output).toMatch(/\u001b\[2m\[\d+\]\u001b\[0m/);
+    expect(output).toContain('\u001b[1m[Auth]\u001b[0m');
+    expect(output).toContain(' Colored');
+    expect(output).not.toContain('\u001b[33m Colored');
+  });
+
+  test('should apply theme overrides to levels and object keys', () => {
+    configure({ color: 'always', theme: { info: 'bold magenta', key: 'yellow', value: 'cyan' } });
+    logger.info({ user: 'alice' });
+
+    const output = readOutput();
+    expect(output).toContain('\u001b[1;35m[Info]\u001b[0m');
+    expect(output).toContain('\u001b[33m"user"\u001b[0m: \u001b[36m"alice"\u001b[0m');
+  });
+
+  test('should reject unknown theme colors', () => {
+    expect(() => configure({ color: true, theme: { error: 'bold crimson' } })).toThrow(
+      /prod\.output\.theme\.error: is invalid: Unknown color 'crimson'/
+    );
+    expect(() => configure({ color: true, theme: { time: 'sparkly' } })).toThrow(logger.ConfigValidationError);
+  });
+
+  test('should honour NO_COLOR', () => {
+    process.env.NO_COLOR = '1';
+    configure({ color: true });
+    logger.error('No color please');
+
+    expect(readOutput()).not.toMatch(ANSI);
+  });
+
+  test('should honour FORCE_COLOR in auto mode', () => {
+    process.env.FORCE_COLOR = '1';
+    configure({ color: 'auto' });
+    logger.error('Forced color');
+
+    expect(readOutput()).toMatch(ANSI);
+  });
+});
@@ -0,0 +1,130 @@
+import * as logger from '../index.js';
+import fs from 'fs';
+import path from 'path';
+
+const rootDir = './test.config-file';
+const logFile = path.join(rootDir, 'app.log');
+
+function write(name, contents) {
+  const file = path.join(rootDir, name);
+  fs.writeFileSync(file, contents);
+  return file;
+}
+
+function messages(file = logFile) {
+  return fs
+    .readFileSync(file, 'utf8')
+    .trim()
+    .split('\n')
+    .map((line) => JSON.parse(line).msg);
+}
+
+describe('Config Files', () => {
+  beforeEach(() => {
+    fs.rmSync(rootDir, { recursive: true, force: true });
+    fs.mkdirSync(rootDir, { recursive: true });
+  });
+
+  afterEach(() => {
+    for (const key of Object.keys(process.env).filter((key) => key.startsWith('EVENTUM_OUTPUT_'))) {
+      delete process.env[key];
+    }
+  });
+
+  afterAll(() => {
+    fs.rmSync(rootDir, { recursive: true, force: true });
+  });
+
+  test('should load a JSON file with named format and target', () => {
+    const file = write(
+      'eventum.json',
+      JSON.stringify({ prod: { output: { format: 'json', target: 'file', filePath: logFile } } })
+    );
+
+    const applied = logger.loadConfig(file);
+    expect(applied.output.format).toBe(logger.OutputFormat.Json);
+    expect(applied.output.target).toBe(logger.OutputTarget.File);
+
+    logger.info('from json');
+    logger.shutdown();
+    expect(messages()).toEqual(['from json']);
+  });
+
+  test('should load a TOML file', () => {
+    const file = write(
+      'eventum.toml',
+      `[prod]
+level = "warn"
+
+[prod.output]
+format = "json"
+target = "file"
+filePath = "${logFile}"
+fileMode = 0o600
+`
+    );
+
+    expect(logger.loadConfig(file)).toBeTruthy();
+    logger.info('filtered');
+    logger.warn('from toml');
+    logger.shutdown();
+    expect(messages()).toEqual(['from toml']);
+  });
+
+  test('should load a YAML file', () => {
+    const file = write(
+      'eventum.yaml',
+      `prod:
+  output:
+    format: 1
+    target: file
+    filePath: ${logFile}
+    color: never
+  fields:
+    pid: true
+`
+    );
+
+    expect(logger.loadConfig(file)).toBeTruthy();
+    logger.info('from yaml');
+    logger.shutdown();
+    expect(messages()).toEqual(['from yaml']);
+  });
+
+  test('should let EVENTUM_OUTPUT_* variables override the config', () => {
+    const otherFile = path.join(rootDir, 'override.log');
+    process.env.EVENTUM_OUTPUT_FORMAT = 'json';
+    process.env.EVENTUM_OUTPUT_TARGET = 'file';
+    process.env.EVENTUM_OUTPUT_FILE_PATH = otherFile;
+    process.env.EVENTUM_OUTPUT_BATCH_ENABLED = 'false';
+
+    const applied = logger.setConfig({ prod: { output: { format: 0, target: 0 } } });
+    expect(applied.output.filePath).toBe(otherFile);
+
+    logger.info('overridden');
+    logger.shutdown();
+    expect(messages(otherFile)).toEqual(['overridden']);
+  });
+
+  test('should reject invalid overrides', () => {
+    process.env.EVENTUM_OUTPUT_FORMAT = 'xml';
+    expect(() => logger.setConfig({ prod: { output: { format: 0, target: 3 } } })).toThrow(
+      /EVENTUM_OUTPUT_FORMAT: 'xml' is not an output format/
+    );
+
+    delete process.env.EVENTUM_OUTPUT_FORMAT;
+    process.env.EVENTUM_OUTPUT_ROTATE_DALY = 'true';
+    expect(() => logger.setConfig({ prod: { output: { format: 0, target: 3 } } })).toThrow(
+      /EVENTUM_OUTPUT_ROTATE_DALY: not an OutputConfig field/
+    );
+  });
+
+  test('should throw for unreadable or malformed files', () => {
+    expect(() => logger.loadConfig(path.join(rootDir, 'missing.json'))).toThrow(/missing\.json/);
+    expect(() => logger.loadConfig(write('eventum.ini', 'format=json'))).toThrow(/\.json, \.toml/);
+    expect(() => logger.loadConfig(write('broken.toml', '[prod\noutput ='))).toThrow(/broken\.toml/);
+    expect(() => logger.loadConfig(write('wrong.json', '{"prod":{"output":{"format":"xml","target":"file"}}}'))).toThrow(
+      /'xml' is not an output format/
+    );
+  });
+});
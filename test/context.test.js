@@ -0,0 +1,220 @@
+import * as logger from '../index.js';
+import fs from 'fs';
+import { createRequire } from 'module';
+
+const cjsLogger = createRequire(import.meta.url)('../index.cjs');
+
+const logFile = './test.context.log';
+
+function readEntries() {
+  return fs
+    .readFileSync(logFile, 'utf8')
+    .trim()
+    .split('\n')
+    .map((line) => JSON.parse(line));
+}
+
+describe('Request Context', () => {
+  beforeEach(() => {
+    fs.writeFileSync(logFile, '');
+
+    const ok = logger.setConfig({
+      prod: {
+        output: {
+          color: false,
+          format: 1, // JSON
+          target: 2, // File
+          filePath: logFile
+        },
+        base: { service: 'checkout' }
+      }
+    });
+    if (!ok) {
+      throw new Error('Logger rejected config');
+    }
+  });
+
+  afterAll(() => {
+    try {
+      if (fs.existsSync(logFile)) {
+        fs.unlinkSync(logFile);
+      }
+    } catch (err) {
+      // Ignore cleanup errors
+    }
+  });
+
+  test('should emit context fields at the top level across await', async () => {
+    const result = await logger.runWithContext(
+      { traceId: '4bf92f3577b34da6a3ce929d0e0e4736', requestId: 'req-1' },
+      async () => {
+        logger.info('before await');
+        await new Promise((resolve) => setTimeout(resolve, 10));
+        logger.setContext({ userId: 42 });
+        logger.warn({ scope: 'Orders', message: 'after await' });
+        return logger.getContext();
+      }
+    );
+    logger.info('outside');
+    logger.shutdown();
+
+    expect(result).toEqual({
+      traceId: '4bf92f3577b34da6a3ce929d0e0e4736',
+      requestId: 'req-1',
+      userId: 42
+    });
+
+    const [before, after, outside] = readEntries();
+    expect(before.traceId).toBe('4bf92f3577b34da6a3ce929d0e0e4736');
+    expect(before.requestId).toBe('req-1');
+    expect(before.userId).toBeUndefined();
+    expect(after.userId).toBe(42);
+    expect(after.scope).toBe('Orders');
+    expect(outside.requestId).toBeUndefined();
+  });
+
+  test('should keep nested context from leaking into the outer one', () => {
+    logger.runWithContext({ requestId: 'outer' }, () => {
+      logger.runWithContext({ requestId: 'inner', step: 1 }, () => {
+        logger.info('inner');
+      });
+      logger.info('outer');
+    });
+    logger.shutdown();
+
+    const [inner, outer] = readEntries();
+    expect(inner.requestId).toBe('inner');
+    expect(inner.step).toBe(1);
+    expect(outer.requestId).toBe('outer');
+    expect(outer.step).toBeUndefined();
+  });
+
+  test('should keep setContext() calls of concurrent branches apart', async () => {
+    const branch = async (userId, delay) => {
+      logger.setContext({ userId });
+      await new Promise((resolve) => setTimeout(resolve, delay));
+      logger.info(`user ${userId}`);
+    };
+
+    await logger.runWithContext({ requestId: 'req-1' }, () =>
+      Promise.all([branch(1, 20), branch(2, 5)])
+    );
+    logger.shutdown();
+
+    const entries = readEntries();
+    expect(entries.map((entry) => [entry.msg, entry.userId, entry.requestId])).toEqual([
+      ['user 2', 2, 'req-1'],
+      ['user 1', 1, 'req-1']
+    ]);
+  });
+
+  test('should share the context between the ESM and CommonJS entry points', () => {
+    logger.runWithContext({ requestId: 'from-esm' }, () => {
+      cjsLogger.info('logged through require');
+    });
+    cjsLogger.runWithContext({ requestId: 'from-cjs' }, () => {
+      expect(logger.getContext()).toEqual({ requestId: 'from-cjs' });
+    });
+    logger.shutdown();
+
+    const [entry] = readEntries();
+    expect(entry.requestId).toBe('from-esm');
+  });
+
+  test('should not let context override built-in fields', () => {
+    logger.runWithContext({ level: 'Fake', service: 'spoofed', msg: 'x', requestId: 'r' }, () => {
+      logger.error('real message');
+    });
+    logger.shutdown();
+
+    const [entry] = readEntries();
+    expect(entry.level).toBe('Error');
+    expect(entry.service).toBe('checkout');
+    expect(entry.msg).toBe('real message');
+    expect(entry.requestId).toBe('r');
+  });
+
+  test('should keep context fields named like base fields that are not emitted', () => {
+    logger.runWithContext({ env: 'staging', region: 'eu-west-1' }, () => {
+      logger.info('tagged');
+    });
+    logger.shutdown();
+
+    const [entry] = readEntries();
+    expect(entry.service).toBe('checkout');
+    expect(entry.env).toBe('staging');
+    expect(entry.region).toBe('eu-west-1');
+  });
+
+  test('should mask context fields', () => {
+    logger.setConfig({
+      prod: {
+        output: {
+          format: 1, // JSON
+          target: 2, // File
+          filePath: logFile,
+          masking: { keyword: '[MASKED]', exact: ['password'], partial: ['token'] }
+        }
+      }
+    });
+
+    logger.runWithContext({ password: 'hunter2', session: { refresh_token: 'abc' }, userId: 7 }, () => {
+      logger.info('login');
+    });
+    logger.shutdown();
+
+    const [entry] = readEntries();
+    expect(entry.password).toBe('[MASKED]');
+    expect(entry.session.refresh_token).toBe('[MASKED]');
+    expect(entry.userId).toBe(7);
+    expect(fs.readFileSync(logFile, 'utf8')).not.toContain('hunter2');
+  });
+
+  test('should only read the context for entries that are logged', () => {
+    logger.setConfig({
+      prod: {
+        output: { format: 1, target: 2, filePath: logFile },
+        level: 'info'
+      }
+    });
+
+    let reads = 0;
+    const payload = {
+      get size() {
+        reads += 1;
+        return 3;
+      }
+    };
+    logger.runWithContext({ payload }, () => {
+      logger.debug('filtered');
+      expect(reads).toBe(0);
+      logger.info('logged');
+      expect(reads).toBe(1);
+    });
+    logger.shutdown();
+
+    const [entry] = readEntries();
+    expect(entry.payload).toEqual({ size: 3 });
+  });
+
+  test('should append context to text output', () => {
+    logger.setConfig({
+      prod: {
+        output: {
+          color: false,
+          format: 0, // Text
+          target: 2, // File
+          filePath: logFile
+        },
+        fields: { time: false }
+      }
+    });
+
+    logger.runWithContext({ requestId: 'abc', attempt: 2 }, () => {
+      logger.info('text entry');
+    });
+    logger.shutdown();
+
+    expect(fs.readFileSync(logFile, 'utf8')).toContain('[attempt=2 requestId=abc]');
+  });
+});
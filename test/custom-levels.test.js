@@ -0,0 +1,129 @@
+import * as logger from '../index.js';
+import fs from 'fs';
+
+const logFile = './test.custom-levels.log';
+
+const customLevels = [
+  { name: 'http', severity: 25, color: 'magenta' },
+  { name: 'notice', severity: 35, color: 'bold blue' },
+  { name: 'audit', severity: 45 }
+];
+
+function configure(format, level) {
+  const ok = logger.setConfig({
+    prod: {
+      output: {
+        color: false,
+        format,
+        target: 2, // File
+        filePath: logFile
+      },
+      fields: {
+        time: false,
+        msg: true,
+        level: true
+      },
+      level,
+      customLevels
+    }
+  });
+  if (!ok) {
+    throw new Error('Logger rejected config');
+  }
+}
+
+function readLines() {
+  return fs.readFileSync(logFile, 'utf8').trim().split('\n').filter(Boolean);
+}
+
+describe('Custom Log Levels', () => {
+  afterEach(() => {
+    try {
+      if (fs.existsSync(logFile)) {
+        fs.unlinkSync(logFile);
+      }
+    } catch (err) {
+      // Ignore cleanup errors
+    }
+  });
+
+  test('should log custom levels by name in JSON output', () => {
+    configure(1, 'trace');
+
+    logger.log('notice', 'Config reloaded');
+    logger.log('AUDIT', { action: 'delete' });
+    logger.log('info', 'Built-in by name');
+
+    const lines = readLines().map((line) => JSON.parse(line));
+    expect(lines.map((entry) => entry.level)).toEqual(['notice', 'audit', 'Info']);
+  });
+
+  test('should filter by severity across built-in and custom levels', () => {
+    configure(1, 'notice');
+
+    logger.debug('dropped debug');
+    logger.log('http', 'dropped http');
+    logger.info('dropped info');
+    logger.log('notice', 'kept notice');
+    logger.warn('kept warn');
+    logger.log('audit', 'kept audit');
+
+    const messages = readLines().map((line) => JSON.parse(line).msg);
+    expect(messages).toEqual(['kept notice', 'kept warn', 'kept audit']);
+  });
+
+  test('should render custom level badge in text output', () => {
+    configure(0, 'trace');
+
+    logger.log('http', 'GET /health 200');
+
+    expect(readLines()[0]).toBe('[http] GET /health 200');
+  });
+
+  test('should trim custom level names', () => {
+    const ok = logger.setConfig({
+      prod: {
+        output: { color: true, format: 0, target: 2, filePath: logFile },
+        fields: { time: false, msg: true, level: true },
+        level: 'notice',
+        customLevels: [{ name: ' notice ', severity: 35, color: 'blue' }]
+      }
+    });
+    expect(ok).toBeTruthy();
+
+    logger.log('notice', 'Trimmed');
+    logger.info('Below the minimum');
+
+    expect(readLines()).toEqual(['\u001b[34m[notice]\u001b[0m Trimmed']);
+  });
+
+  test('should throw for unknown level names', () => {
+    configure(1, 'trace');
+
+    expect(() => logger.log('verbose', 'nope')).toThrow("Unknown log level 'verbose'");
+  });
+
+  test('should reject custom levels that reuse a built-in name', () => {
+    const configure = () =>
+      logger.setConfig({
+        prod: {
+          output: { format: 1, target: 3 },
+          customLevels: [{ name: 'Info', severity: 31 }]
+        }
+      });
+
+    expect(configure).toThrow(/prod\.customLevels\[0\]\.name: 'Info' conflicts with a built-in level/);
+  });
+
+  test('should reject an unknown minimum level', () => {
+    const configure = () =>
+      logger.setConfig({
+        prod: {
+          output: { format: 1, target: 3 },
+          level: 'verbose'
+        }
+      });
+
+    expect(configure).toThrow(/prod\.level: 'verbose' is not a known log level/);
+  });
+});
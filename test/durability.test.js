@@ -0,0 +1,95 @@
+import * as logger from '../index.js';
+import { spawn } from 'child_process';
+import fs from 'fs';
+
+const logFile = './test.durability.log';
+const indexPath = new URL('../index.js', import.meta.url).href;
+
+function configure(output) {
+  return logger.setConfig({
+    prod: {
+      output: {
+        color: false,
+        format: 1, // JSON
+        target: 2, // File
+        filePath: logFile,
+        ...output
+      }
+    }
+  });
+}
+
+function entries() {
+  return fs
+    .readFileSync(logFile, 'utf8')
+    .trim()
+    .split('\n')
+    .map((line) => JSON.parse(line));
+}
+
+// Logs `count` entries of about 1 KB from a separate process.
+function writer(id, count) {
+  const script = `
+    import * as logger from ${JSON.stringify(indexPath)};
+    logger.setConfig({
+      prod: {
+        output: { format: 1, target: 2, filePath: ${JSON.stringify(logFile)}, batchEnabled: true, batchSize: 20 }
+      }
+    });
+    for (let i = 0; i < ${count}; i++) {
+      logger.info({ writer: ${id}, i, padding: 'x'.repeat(1000) });
+    }
+    logger.shutdown();
+  `;
+  return new Promise((resolve, reject) => {
+    const child = spawn(process.execPath, ['--input-type=module', '-e', script], { stdio: 'inherit' });
+    child.on('error', reject);
+    child.on('exit', (code) => (code === 0 ? resolve() : reject(new Error(`writer exited with ${code}`))));
+  });
+}
+
+describe('File Durability', () => {
+  beforeEach(() => {
+    fs.rmSync(logFile, { force: true });
+  });
+
+  afterAll(() => {
+    fs.rmSync(logFile, { force: true });
+  });
+
+  test('should write every entry under each fsync policy', () => {
+    for (const fsync of ['never', 'everyBatch', 'interval', 'onLevel']) {
+      expect(configure({ fsync, fsyncIntervalMs: 10, fsyncLevel: 'warn' })).toBeTruthy();
+      logger.info(`synced ${fsync}`);
+      logger.error(`synced ${fsync} error`);
+      logger.shutdown();
+    }
+
+    expect(entries().map((entry) => entry.msg)).toEqual([
+      'synced never',
+      'synced never error',
+      'synced everyBatch',
+      'synced everyBatch error',
+      'synced interval',
+      'synced interval error',
+      'synced onLevel',
+      'synced onLevel error'
+    ]);
+  });
+
+  test('should not interleave lines written by several processes', async () => {
+    await Promise.all([1, 2, 3, 4].map((id) => writer(id, 200)));
+
+    const written = entries();
+    expect(written).toHaveLength(800);
+    for (const id of [1, 2, 3, 4]) {
+      const own = written.filter((entry) => entry.msg.writer === id).map((entry) => entry.msg.i);
+      expect(own).toEqual([...Array(200).keys()]);
+    }
+  });
+
+  test('should reject invalid fsync settings', () => {
+    expect(() => configure({ fsync: 'interval', fsyncIntervalMs: 0 })).toThrow(/output\.fsyncIntervalMs/);
+    expect(() => configure({ fsync: 'onLevel', fsyncLevel: 'loud' })).toThrow(/output\.fsyncLevel/);
+  });
+});
@@ -0,0 +1,129 @@
+import * as logger from '../index.js';
+import fs from 'fs';
+import path from 'path';
+
+const rootDir = './test.environments';
+
+function messages(file) {
+  return fs
+    .readFileSync(file, 'utf8')
+    .trim()
+    .split('\n')
+    .map((line) => JSON.parse(line).msg);
+}
+
+const environments = {
+  prod: {
+    level: 'info',
+    output: {
+      format: logger.OutputFormat.Json,
+      target: logger.OutputTarget.File,
+      filePath: path.join(rootDir, 'prod.log'),
+      batchEnabled: false
+    }
+  },
+  staging: {
+    extends: 'prod',
+    level: 'debug',
+    output: { filePath: path.join(rootDir, 'staging.log') }
+  },
+  canary: {
+    extends: 'staging',
+    output: { batchEnabled: null }
+  },
+  test: {
+    output: { format: logger.OutputFormat.Json, target: logger.OutputTarget.Null }
+  }
+};
+
+describe('Named Environments', () => {
+  const originalNodeEnv = process.env.NODE_ENV;
+
+  beforeEach(() => {
+    fs.rmSync(rootDir, { recursive: true, force: true });
+  });
+
+  afterEach(() => {
+    process.env.NODE_ENV = originalNodeEnv;
+    delete process.env.APP_ENV;
+  });
+
+  afterAll(() => {
+    logger.shutdown();
+    fs.rmSync(rootDir, { recursive: true, force: true });
+  });
+
+  test('should select an environment by name', () => {
+    const applied = logger.setConfig({ environments, env: 'test' });
+    expect(applied.output.target).toBe(logger.OutputTarget.Null);
+  });
+
+  test('should select an environment from NODE_ENV by default', () => {
+    process.env.NODE_ENV = 'test';
+    const applied = logger.setConfig({ environments });
+    expect(applied.output.target).toBe(logger.OutputTarget.Null);
+  });
+
+  test('should select an environment from a custom variable', () => {
+    process.env.APP_ENV = 'staging';
+    const applied = logger.setConfig({ environments, envVar: 'APP_ENV' });
+    expect(applied.output.filePath).toBe(path.join(rootDir, 'staging.log'));
+  });
+
+  test('should deep-merge an environment over the one it extends', () => {
+    const applied = logger.setConfig({ environments, env: 'staging' });
+    expect(applied.level).toBe('debug');
+    expect(applied.output.format).toBe(logger.OutputFormat.Json);
+    expect(applied.output.target).toBe(logger.OutputTarget.File);
+    expect(applied.output.batchEnabled).toBe(false);
+
+    logger.debug('from staging');
+    logger.shutdown();
+    expect(messages(path.join(rootDir, 'staging.log'))).toEqual(['from staging']);
+  });
+
+  test('should follow extends chains and remove fields set to null', () => {
+    const applied = logger.setConfig({ environments, env: 'canary' });
+    expect(applied.level).toBe('debug');
+    expect(applied.output.filePath).toBe(path.join(rootDir, 'staging.log'));
+    expect(applied.output.batchEnabled).toBeUndefined();
+  });
+
+  test('should let named environments extend top-level dev and prod', () => {
+    const applied = logger.setConfig({
+      prod: { output: { format: logger.OutputFormat.Json, target: logger.OutputTarget.Null } },
+      environments: { staging: { extends: 'prod', level: 'warn' } },
+      env: 'staging'
+    });
+    expect(applied.level).toBe('warn');
+    expect(applied.output.target).toBe(logger.OutputTarget.Null);
+  });
+
+  test('should map production and development to prod and dev', () => {
+    process.env.NODE_ENV = 'production';
+    const applied = logger.setConfig({ environments });
+    expect(applied.output.filePath).toBe(path.join(rootDir, 'prod.log'));
+  });
+
+  test('should reject a missing environment', () => {
+    expect(() => logger.setConfig({ environments, env: 'qa' })).toThrow(
+      /env: 'qa' is not a defined environment \(available: canary, prod, staging, test\)/
+    );
+    expect(() => logger.setConfig({ environments: { a: { extends: 'b' }, b: { extends: 'a' } }, env: 'a' })).toThrow(
+      /environments\.b\.extends: forms a cycle: a -> b -> a/
+    );
+    expect(() => logger.setConfig({ environments: { a: { extends: 'missing' } }, env: 'a' })).toThrow(
+      /environments\.a\.extends: 'missing' is not a defined environment/
+    );
+    expect(() => logger.setConfig({ environments, envVar: 'APP_ENV' })).toThrow(/env: must be set when the APP_ENV variable is not/);
+  });
+
+  test('should keep dev and prod selection without environments', () => {
+    process.env.NODE_ENV = 'staging';
+    const applied = logger.setConfig({
+      dev: { output: { format: logger.OutputFormat.Text, target: logger.OutputTarget.Null } },
+      prod: environments.prod
+    });
+    expect(applied.output.format).toBe(logger.OutputFormat.Text);
+  });
+});
@@ -0,0 +1,112 @@
+import * as logger from '../index.js';
+import fs from 'fs';
+import http from 'http';
+
+const logFile = './test.fallback.log';
+
+function waitFor(check, timeout = 3000) {
+  return new Promise((resolve, reject) => {
+    const started = Date.now();
+    const timer = setInterval(() => {
+      if (check()) {
+        clearInterval(timer);
+        resolve();
+      } else if (Date.now() - started > timeout) {
+        clearInterval(timer);
+        reject(new Error('Timed out waiting for fallback output'));
+      }
+    }, 20);
+  });
+}
+
+function readLog() {
+  return fs.existsSync(logFile) ? fs.readFileSync(logFile, 'utf8') : '';
+}
+
+describe('Fallback Chain', () => {
+  let server;
+  let url;
+  let requests = [];
+  let failing = true;
+
+  beforeAll(async () => {
+    server = http.createServer((req, res) => {
+      const chunks = [];
+      req.on('data', (chunk) => chunks.push(chunk));
+      req.on('end', () => {
+        if (failing) {
+          res.statusCode = 503;
+        } else {
+          requests.push(Buffer.concat(chunks).toString());
+        }
+        res.end();
+      });
+    });
+    await new Promise((resolve) => server.listen(0, '127.0.0.1', resolve));
+    url = `http://127.0.0.1:${server.address().port}/ingest`;
+  });
+
+  afterAll(() => {
+    logger.shutdown();
+    server.close();
+    fs.rmSync(logFile, { force: true });
+  });
+
+  test('should reject a fallback that repeats the primary target', () => {
+    const configure = () =>
+      logger.setConfig({
+        prod: {
+          output: { format: 1, target: 0, fallback: [1, 0] }
+        }
+      });
+
+    expect(configure).toThrow(/prod\.output\.fallback\[1\]: repeats the Stdout target/);
+  });
+
+  test('should require settings for every sink in the chain', () => {
+    const configure = () =>
+      logger.setConfig({
+        prod: {
+          output: { format: 1, target: 0, fallback: [2] } // File without filePath
+        }
+      });
+
+    expect(configure).toThrow(/prod\.output\.filePath: must be set when using the File target/);
+  });
+
+  test('should fall back to the next sink and switch back after the cool-down', async () => {
+    const ok = logger.setConfig({
+      prod: {
+        output: {
+          format: 1, // JSON
+          target: 5, // Http
+          batchIntervalMs: 20,
+          http: { url },
+          filePath: logFile,
+          fallback: [2, 1], // File, then Stderr
+          fallbackCooldownMs: 300
+        },
+        fields: { time: false, msg: true, level: true }
+      }
+    });
+    expect(ok).toBeTruthy();
+
+    logger.info('while collector is down');
+    await waitFor(() => readLog().includes('while collector is down'));
+    expect(logger.getSinkFailures().http).toBe(1);
+
+    // The open circuit sends entries straight to the file without touching Http.
+    failing = false;
+    logger.info('during cool-down');
+    await waitFor(() => readLog().includes('during cool-down'));
+    expect(requests).toHaveLength(0);
+    expect(logger.getSinkFailures().http).toBe(1);
+
+    await new Promise((resolve) => setTimeout(resolve, 350));
+    logger.info('after recovery');
+    await waitFor(() => requests.length >= 1);
+
+    expect(requests[0]).toBe('[{"level":"Info","msg":"after recovery"}]');
+    expect(readLog()).not.toContain('after recovery');
+  });
+});
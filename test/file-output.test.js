@@ -0,0 +1,93 @@
+import * as logger from '../index.js';
+import fs from 'fs';
+import path from 'path';
+
+const rootDir = './test.file-output';
+
+function today() {
+  const now = new Date();
+  const pad = (n) => String(n).padStart(2, '0');
+  return `app_${now.getUTCFullYear()}-${pad(now.getUTCMonth() + 1)}-${pad(now.getUTCDate())}.log`;
+}
+
+function configure(filePath, output) {
+  return logger.setConfig({
+    prod: {
+      output: {
+        color: false,
+        format: 0, // Text
+        target: 2, // File
+        filePath,
+        ...output
+      },
+      fields: { time: false }
+    }
+  });
+}
+
+const unixOnly = test.skipIf(process.platform === 'win32');
+
+describe('File Output', () => {
+  beforeEach(() => {
+    fs.rmSync(rootDir, { recursive: true, force: true });
+  });
+
+  afterAll(() => {
+    fs.rmSync(rootDir, { recursive: true, force: true });
+  });
+
+  test('should create missing parent directories', () => {
+    const filePath = path.join(rootDir, 'nested', 'logs', 'app.log');
+    expect(configure(filePath)).toBeTruthy();
+
+    logger.info('in a new directory');
+    logger.shutdown();
+
+    expect(fs.readFileSync(filePath, 'utf8')).toContain('in a new directory');
+  });
+
+  unixOnly('should create files and directories with the configured modes', () => {
+    const dir = path.join(rootDir, 'private');
+    const filePath = path.join(dir, 'app.log');
+    expect(configure(filePath, { fileMode: 0o600, dirMode: 0o700 })).toBeTruthy();
+
+    logger.info('private entry');
+    logger.shutdown();
+
+    expect(fs.statSync(dir).mode & 0o777).toBe(0o700);
+    expect(fs.statSync(filePath).mode & 0o777).toBe(0o600);
+  });
+
+  unixOnly('should point the current link at the file being written', () => {
+    const filePath = path.join(rootDir, 'linked', 'app.log');
+    expect(configure(filePath, { rotateDaily: true, currentLink: true })).toBeTruthy();
+
+    logger.info('through the link');
+    logger.shutdown();
+
+    expect(fs.readlinkSync(filePath)).toBe(today());
+    expect(fs.readFileSync(filePath, 'utf8')).toContain('through the link');
+  });
+
+  unixOnly('should not replace a regular file with the current link', () => {
+    const dir = path.join(rootDir, 'regular');
+    const filePath = path.join(dir, 'app.log');
+    fs.mkdirSync(dir, { recursive: true });
+    fs.writeFileSync(filePath, 'keep me\n');
+    expect(configure(filePath, { rotateDaily: true, currentLink: true })).toBeTruthy();
+
+    logger.info('daily entry');
+    logger.shutdown();
+
+    expect(fs.lstatSync(filePath).isSymbolicLink()).toBe(false);
+    expect(fs.readFileSync(filePath, 'utf8')).toBe('keep me\n');
+    expect(fs.readFileSync(path.join(dir, today()), 'utf8')).toContain('daily entry');
+  });
+
+  test('should reject invalid file settings', () => {
+    const filePath = path.join(rootDir, 'invalid', 'app.log');
+    expect(() => configure(filePath, { currentLink: true })).toThrow(/output\.currentLink/);
+    expect(() => configure(filePath, { fileMode: 0o17777 })).toThrow(/output\.fileMode/);
+    expect(() => configure(filePath, { dirMode: 0o17777 })).toThrow(/output\.dirMode/);
+  });
+});
@@ -0,0 +1,191 @@
+import * as logger from '../index.js';
+import http from 'http';
+import zlib from 'zlib';
+
+function waitFor(check, timeout = 3000) {
+  return new Promise((resolve, reject) => {
+    const started = Date.now();
+    const timer = setInterval(() => {
+      if (check()) {
+        clearInterval(timer);
+        resolve();
+      } else if (Date.now() - started > timeout) {
+        clearInterval(timer);
+        reject(new Error('Timed out waiting for HTTP requests'));
+      }
+    }, 20);
+  });
+}
+
+describe('HTTP Target', () => {
+  let server;
+  let url;
+  let requests = [];
+  let failuresLeft = 0;
+
+  beforeAll(async () => {
+    server = http.createServer((req, res) => {
+      const chunks = [];
+      req.on('data', (chunk) => chunks.push(chunk));
+      req.on('end', () => {
+        let body = Buffer.concat(chunks);
+        if (req.headers['content-encoding'] === 'gzip') {
+          body = zlib.gunzipSync(body);
+        }
+        requests.push({ headers: req.headers, body: body.toString() });
+
+        if (failuresLeft > 0) {
+          failuresLeft--;
+          res.statusCode = 503;
+        }
+        res.end();
+      });
+    });
+    await new Promise((resolve) => server.listen(0, '127.0.0.1', resolve));
+    url = `http://127.0.0.1:${server.address().port}/ingest`;
+  });
+
+  beforeEach(() => {
+    requests = [];
+    failuresLeft = 0;
+  });
+
+  afterAll(() => {
+    logger.shutdown();
+    server.closeAllConnections();
+    server.close();
+  });
+
+  function configure(http, format = 1) {
+    return logger.setConfig({
+      prod: {
+        output: {
+          format,
+          target: 5, // Http
+          batchSize: 10,
+          batchIntervalMs: 50,
+          http: { url, ...http }
+        },
+        fields: { time: false, msg: true, level: true }
+      }
+    });
+  }
+
+  test('should reject Http target without url', () => {
+    const configure = () =>
+      logger.setConfig({
+        prod: { output: { format: 1, target: 5 } }
+      });
+    expect(configure).toThrow(/prod\.output\.http\.url: must be set when using the Http target/);
+  });
+
+  test('should POST batches as a JSON array with custom headers', async () => {
+    expect(configure({ headers: { 'X-Api-Key': 'secret' } })).toBeTruthy();
+
+    logger.info('first');
+    logger.warn({ scope: 'Billing', message: 'second' });
+
+    await waitFor(() => requests.flatMap((r) => JSON.parse(r.body)).length >= 2);
+
+    const [request] = requests;
+    expect(request.headers['content-type']).toBe('application/json');
+    expect(request.headers['x-api-key']).toBe('secret');
+
+    const entries = requests.flatMap((r) => JSON.parse(r.body));
+    expect(entries[0]).toEqual({ level: 'Info', msg: 'first' });
+    expect(entries[1]).toMatchObject({ level: 'Warn', msg: { message: 'second' }, scope: 'Billing' });
+  });
+
+  test('should send gzip-compressed NDJSON bodies', async () => {
+    expect(configure({ bodyFormat: 'ndjson', gzip: true })).toBeTruthy();
+
+    logger.info('compressed');
+
+    await waitFor(() => requests.length >= 1);
+
+    const [request] = requests;
+    expect(request.headers['content-type']).toBe('application/x-ndjson');
+    expect(request.headers['content-encoding']).toBe('gzip');
+    expect(request.body).toBe('{"level":"Info","msg":"compressed"}\n');
+  });
+
+  test('should send text lines as JSON strings', async () => {
+    expect(configure({}, 0)).toBeTruthy();
+
+    logger.error('plain text');
+
+    await waitFor(() => requests.length >= 1);
+
+    const [line] = JSON.parse(requests[0].body);
+    expect(line).toContain('[Error]');
+    expect(line).toContain('plain text');
+  });
+
+  test('should retry batches after a 5xx response', async () => {
+    failuresLeft = 2;
+    expect(configure({ retryBaseMs: 20, retryMaxMs: 50 })).toBeTruthy();
+
+    logger.info('eventually delivered');
+
+    await waitFor(() => requests.length >= 3);
+
+    expect(requests.map((r) => r.body)).toEqual(
+      Array(3).fill('[{"level":"Info","msg":"eventually delivered"}]')
+    );
+  });
+
+  test('should keep retrying queued batches after the url changes', async () => {
+    failuresLeft = 1;
+    expect(configure({ retryBaseMs: 100, retryMaxMs: 100 })).toBeTruthy();
+
+    logger.info('queued before the switch');
+    await waitFor(() => requests.length >= 1);
+
+    expect(
+      logger.setConfig({
+        prod: {
+          output: {
+            format: 1,
+            target: 5, // Http
+            batchIntervalMs: 50,
+            http: { url: `${url}?v=2` }
+          }
+        }
+      })
+    ).toBeTruthy();
+    logger.info('sent after the switch');
+
+    await waitFor(
+      () => requests.filter((r) => r.body.includes('queued before the switch')).length >= 2
+    );
+    expect(requests.some((r) => r.body.includes('sent after the switch'))).toBe(true);
+  });
+
+  test('should bound the shutdown drain of queued batches', async () => {
+    failuresLeft = Infinity;
+    expect(
+      logger.setConfig({
+        prod: {
+          output: {
+            format: 1,
+            target: 5, // Http
+            batchSize: 1,
+            batchIntervalMs: 20,
+            http: { url, timeoutMs: 10000, retryBaseMs: 60000, maxPendingRetries: 10 }
+          }
+        }
+      })
+    ).toBeTruthy();
+
+    for (let i = 0; i < 3; i++) {
+      logger.info(`queued ${i}`);
+      await waitFor(() => requests.length > i);
+    }
+
+    // shutdown() blocks the event loop, so the server never answers the
+    // retries: each one would wait for the full 10s timeout.
+    const started = Date.now();
+    logger.shutdown();
+    expect(Date.now() - started).toBeLessThan(8000);
+  }, 15000);
+});
@@ -0,0 +1,38 @@
+import * as logger from '../index.js';
+
+describe('Journald Target', () => {
+  afterAll(() => {
+    logger.shutdown();
+  });
+
+  test('should accept a journald config', () => {
+    const result = logger.setConfig({
+      prod: {
+        output: {
+          format: 0, // Text
+          target: 8, // Journald
+          journald: { identifier: 'checkout' }
+        }
+      }
+    });
+
+    expect(result).toBeTruthy();
+    expect(result.output.journald.identifier).toBe('checkout');
+  });
+
+  test('should fall back to stderr when the journal socket is missing', () => {
+    logger.setConfig({
+      prod: {
+        output: {
+          format: 0,
+          target: 8,
+          journald: { socketPath: '/nonexistent/journal/socket' }
+        }
+      }
+    });
+
+    expect(() => {
+      logger.warn({ scope: 'payments', message: 'Card declined', user: { id: 42 } });
+    }).not.toThrow();
+  });
+});
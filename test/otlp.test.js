@@ -0,0 +1,154 @@
+import * as logger from '../index.js';
+import http from 'http';
+
+function waitFor(check, timeout = 3000) {
+  return new Promise((resolve, reject) => {
+    const started = Date.now();
+    const timer = setInterval(() => {
+      if (check()) {
+        clearInterval(timer);
+        resolve();
+      } else if (Date.now() - started > timeout) {
+        clearInterval(timer);
+        reject(new Error('Timed out waiting for OTLP requests'));
+      }
+    }, 20);
+  });
+}
+
+function attributes(list) {
+  return Object.fromEntries(list.map(({ key, value }) => [key, value]));
+}
+
+describe('OTLP Target', () => {
+  let server;
+  let endpoint;
+  let requests = [];
+
+  beforeAll(async () => {
+    // Mock collector: records every export request and answers like OTLP/HTTP.
+    server = http.createServer((req, res) => {
+      const chunks = [];
+      req.on('data', (chunk) => chunks.push(chunk));
+      req.on('end', () => {
+        requests.push({ url: req.url, headers: req.headers, body: Buffer.concat(chunks) });
+        res.setHeader('Content-Type', req.headers['content-type']);
+        res.end();
+      });
+    });
+    await new Promise((resolve) => server.listen(0, '127.0.0.1', resolve));
+    endpoint = `http://127.0.0.1:${server.address().port}/v1/logs`;
+  });
+
+  beforeEach(() => {
+    requests = [];
+  });
+
+  afterAll(() => {
+    logger.shutdown();
+    server.close();
+  });
+
+  function configure(otlp) {
+    return logger.setConfig({
+      prod: {
+        output: {
+          format: 1, // JSON
+          target: 9, // Otlp
+          batchIntervalMs: 50,
+          otlp: { endpoint, ...otlp }
+        },
+        base: { service: 'checkout', version: '1.4.2' }
+      }
+    });
+  }
+
+  test('should export JSON-encoded log records with resource attributes', async () => {
+    expect(configure({ encoding: 'json', resourceAttributes: { team: 'payments' } })).toBeTruthy();
+
+    logger.warn({ scope: 'Billing', message: 'Card declined', attempt: 2 });
+    logger.fatal('Out of memory');
+
+    await waitFor(() => requests.length >= 1);
+
+    const [request] = requests;
+    expect(request.url).toBe('/v1/logs');
+    expect(request.headers['content-type']).toBe('application/json');
+
+    const [resourceLogs] = JSON.parse(request.body.toString()).resourceLogs;
+    const resource = attributes(resourceLogs.resource.attributes);
+    expect(resource['service.name']).toEqual({ stringValue: 'checkout' });
+    expect(resource['service.version']).toEqual({ stringValue: '1.4.2' });
+    expect(resource['process.pid']).toEqual({ intValue: String(process.pid) });
+    expect(resource['host.name'].stringValue).toBeTruthy();
+    expect(resource.team).toEqual({ stringValue: 'payments' });
+
+    const [scopeLogs] = resourceLogs.scopeLogs;
+    expect(scopeLogs.scope.name).toBe('eventum');
+
+    const [warn, fatal] = scopeLogs.logRecords;
+    expect(warn.severityNumber).toBe(13);
+    expect(warn.severityText).toBe('Warn');
+    expect(warn.timeUnixNano).toMatch(/^\d{19}$/);
+    expect(attributes(warn.body.kvlistValue.values)).toEqual({
+      message: { stringValue: 'Card declined' },
+      attempt: { intValue: '2' }
+    });
+    expect(attributes(warn.attributes).scope).toEqual({ stringValue: 'Billing' });
+
+    expect(fatal.severityNumber).toBe(21);
+    expect(fatal.body).toEqual({ stringValue: 'Out of memory' });
+  });
+
+  test('should export protobuf-encoded requests by default', async () => {
+    expect(configure({})).toBeTruthy();
+
+    logger.info('protobuf entry');
+
+    await waitFor(() => requests.length >= 1);
+
+    const [request] = requests;
+    expect(request.headers['content-type']).toBe('application/x-protobuf');
+    // ExportLogsServiceRequest starts with field 1 (resource_logs), wire type 2.
+    expect(request.body[0]).toBe(0x0a);
+    expect(request.body.includes(Buffer.from('protobuf entry'))).toBe(true);
+    expect(request.body.includes(Buffer.from('service.name'))).toBe(true);
+  });
+
+  test('should map context ids onto the log record', async () => {
+    expect(configure({ encoding: 'json' })).toBeTruthy();
+
+    logger.runWithContext(
+      { traceId: '4bf92f3577b34da6a3ce929d0e0e4736', spanId: '00f067aa0ba902b7', requestId: 'req-1' },
+      () => logger.info('traced entry')
+    );
+    logger.runWithContext({ traceId: 'not-a-trace-id' }, () => logger.info('untraced entry'));
+
+    await waitFor(() => requests.length >= 1);
+
+    const [traced, untraced] = JSON.parse(requests[0].body.toString()).resourceLogs[0].scopeLogs[0]
+      .logRecords;
+    expect(traced.traceId).toBe('4bf92f3577b34da6a3ce929d0e0e4736');
+    expect(traced.spanId).toBe('00f067aa0ba902b7');
+    expect(attributes(traced.attributes).requestId).toEqual({ stringValue: 'req-1' });
+    expect(attributes(traced.attributes).traceId).toBeUndefined();
+
+    expect(untraced.traceId).toBeUndefined();
+    expect(attributes(untraced.attributes).traceId).toEqual({ stringValue: 'not-a-trace-id' });
+  });
+
+  test('should reject non-HTTP endpoints', () => {
+    const configure = () =>
+      logger.setConfig({
+        prod: {
+          output: {
+            format: 1,
+            target: 9,
+            otlp: { endpoint: 'grpc://collector:4317' }
+          }
+        }
+      });
+
+    expect(configure).toThrow(/prod\.output\.otlp\.endpoint: 'grpc:\/\/collector:4317' must be an http:\/\/ or https:\/\/ URL/);
+  });
+});
@@ -3,12 +3,14 @@ import fs from 'fs';
 
 const jsonFile = './test.output-json.log';
 const textFile = './test.output-text.log';
+const logfmtFile = './test.output-logfmt.log';
 
 describe('Output Formats', () => {
   afterAll(() => {
     try {
       if (fs.existsSync(jsonFile)) fs.unlinkSync(jsonFile);
       if (fs.existsSync(textFile)) fs.unlinkSync(textFile);
+      if (fs.existsSync(logfmtFile)) fs.unlinkSync(logfmtFile);
     } catch (err) {
       // Ignore cleanup errors
     }
@@ -63,4 +65,33 @@ describe('Output Formats', () => {
       done();
     }, 500);
   });
+
+  test('should output logfmt format', (done) => {
+    const logfmtConfig = {
+      prod: {
+        output: {
+          color: false,
+          format: 2, // Logfmt
+          target: 2, // File
+          filePath: logfmtFile
+        },
+        fields: { time: false, msg: true, level: true }
+      }
+    };
+
+    logger.setConfig(logfmtConfig);
+    logger.info('Logfmt format test');
+    logger.warn({ scope: 'Orders', orderId: 42, note: 'say "hi"\nbye', items: ['a', 'b'] });
+    logger.shutdown();
+
+    setTimeout(() => {
+      const lines = fs.readFileSync(logfmtFile, 'utf8').trim().split('\n');
+
+      expect(lines).toEqual([
+        'level=Info msg="Logfmt format test"',
+        'level=Warn scope=Orders items="[\\"a\\",\\"b\\"]" note="say \\"hi\\"\\nbye" orderId=42'
+      ]);
+      done();
+    }, 500);
+  });
 });
@@ -0,0 +1,92 @@
+import * as logger from '../index.js';
+import fs from 'fs';
+
+const logFile = './test.reopen.log';
+const movedFile = './test.reopen.log.1';
+
+function configure(output) {
+  return logger.setConfig({
+    prod: {
+      output: {
+        color: false,
+        format: 1, // JSON
+        target: 2, // File
+        filePath: logFile,
+        ...output
+      }
+    }
+  });
+}
+
+function messages(file) {
+  return fs
+    .readFileSync(file, 'utf8')
+    .trim()
+    .split('\n')
+    .filter(Boolean)
+    .map((line) => JSON.parse(line).msg);
+}
+
+describe('External Rotation', () => {
+  beforeEach(() => {
+    for (const file of [logFile, movedFile]) {
+      fs.rmSync(file, { force: true });
+    }
+    expect(configure()).toBeTruthy();
+  });
+
+  afterAll(() => {
+    for (const file of [logFile, movedFile]) {
+      fs.rmSync(file, { force: true });
+    }
+  });
+
+  test('should write to a new file after the old one was moved away', () => {
+    logger.info('before rotation');
+    fs.renameSync(logFile, movedFile);
+    logger.info('after rotation');
+    logger.shutdown();
+
+    expect(messages(movedFile)).toEqual(['before rotation']);
+    expect(messages(logFile)).toEqual(['after rotation']);
+  });
+
+  test('should write from the start after the file was truncated', () => {
+    logger.info('first');
+    logger.info('second');
+    fs.copyFileSync(logFile, movedFile);
+    fs.truncateSync(logFile, 0);
+    logger.info('third');
+    logger.shutdown();
+
+    expect(messages(movedFile)).toEqual(['first', 'second']);
+    expect(fs.readFileSync(logFile, 'utf8').includes('\0')).toBe(false);
+    expect(messages(logFile)).toEqual(['third']);
+  });
+
+  test('should recreate the file on reopen()', () => {
+    logger.info('before reopen');
+    fs.unlinkSync(logFile);
+    logger.reopen();
+    logger.info('after reopen');
+    logger.shutdown();
+
+    expect(messages(logFile)).toEqual(['after reopen']);
+  });
+
+  test('should reopen on SIGHUP when enabled', async () => {
+    expect(configure({ reopenSignal: true })).toBeTruthy();
+    expect(process.listenerCount('SIGHUP')).toBe(1);
+
+    logger.info('before signal');
+    process.kill(process.pid, 'SIGHUP');
+    await new Promise((resolve) => setTimeout(resolve, 50));
+    logger.info('after signal');
+    logger.shutdown();
+
+    expect(messages(logFile)).toEqual(['before signal', 'after signal']);
+
+    expect(configure({ reopenSignal: false })).toBeTruthy();
+    expect(process.listenerCount('SIGHUP')).toBe(0);
+  });
+});
@@ -0,0 +1,108 @@
+import * as logger from '../index.js';
+import fs from 'fs';
+import path from 'path';
+
+const rootDir = './test.rotation-events';
+
+function today(suffix = '') {
+  const now = new Date();
+  const pad = (n) => String(n).padStart(2, '0');
+  return `app_${now.getUTCFullYear()}-${pad(now.getUTCMonth() + 1)}-${pad(now.getUTCDate())}${suffix}.log`;
+}
+
+function configure(dir, output) {
+  fs.rmSync(dir, { recursive: true, force: true });
+  return logger.setConfig({
+    prod: {
+      output: {
+        color: false,
+        format: 0, // Text
+        target: 2, // File
+        filePath: path.join(dir, 'app.log'),
+        ...output
+      },
+      fields: { time: false }
+    }
+  });
+}
+
+const delivered = () => new Promise((resolve) => setTimeout(resolve, 50));
+
+describe('Rotation Events', () => {
+  let events;
+
+  beforeEach(() => {
+    events = [];
+    logger.onRotate((event) => events.push(event));
+  });
+
+  afterEach(() => {
+    logger.onRotate(null);
+  });
+
+  afterAll(() => {
+    fs.rmSync(rootDir, { recursive: true, force: true });
+  });
+
+  test('should report size rotation with the rotated file', async () => {
+    const dir = path.join(rootDir, 'size');
+    expect(configure(dir, { maxFileSize: 10 })).toBeTruthy();
+
+    logger.info('first entry that fills the file');
+    logger.info('second entry');
+    logger.shutdown();
+    await delivered();
+
+    expect(events).toEqual([
+      { oldPath: path.join(dir, 'app.log.1'), newPath: path.join(dir, 'app.log'), reason: 'size' }
+    ]);
+    expect(fs.readFileSync(events[0].oldPath, 'utf8')).toContain('first entry');
+  });
+
+  test('should rotate on demand', async () => {
+    const dir = path.join(rootDir, 'manual');
+    expect(configure(dir)).toBeTruthy();
+    expect(logger.rotate()).toBe(false);
+
+    logger.info('before rotate');
+    expect(logger.rotate()).toBe(true);
+    logger.info('after rotate');
+    logger.shutdown();
+    await delivered();
+
+    expect(events).toEqual([
+      { oldPath: path.join(dir, 'app.log.1'), newPath: path.join(dir, 'app.log'), reason: 'manual' }
+    ]);
+    expect(fs.readFileSync(path.join(dir, 'app.log.1'), 'utf8')).toContain('before rotate');
+    expect(fs.readFileSync(path.join(dir, 'app.log'), 'utf8')).toContain('after rotate');
+  });
+
+  test('should rotate a time-based file on demand to the next index', async () => {
+    const dir = path.join(rootDir, 'daily');
+    expect(configure(dir, { rotateDaily: true })).toBeTruthy();
+
+    logger.info('before rotate');
+    expect(logger.rotate()).toBe(true);
+    logger.info('after rotate');
+    logger.shutdown();
+    await delivered();
+
+    expect(events).toEqual([
+      { oldPath: path.join(dir, today()), newPath: path.join(dir, today('.1')), reason: 'manual' }
+    ]);
+    expect(fs.readFileSync(path.join(dir, today('.1')), 'utf8')).toContain('after rotate');
+  });
+
+  test('should stop reporting once the callback is removed', async () => {
+    const dir = path.join(rootDir, 'removed');
+    expect(configure(dir)).toBeTruthy();
+    logger.onRotate(null);
+
+    logger.info('entry');
+    expect(logger.rotate()).toBe(true);
+    logger.shutdown();
+    await delivered();
+
+    expect(events).toEqual([]);
+  });
+});
@@ -0,0 +1,244 @@
+import * as logger from '../index.js';
+import fs from 'fs';
+import path from 'path';
+
+const rootDir = './test.rotation';
+
+function today(pattern) {
+  const now = new Date();
+  const pad = (n) => String(n).padStart(2, '0');
+  return pattern
+    .replace('%Y', now.getUTCFullYear())
+    .replace('%m', pad(now.getUTCMonth() + 1))
+    .replace('%d', pad(now.getUTCDate()))
+    .replace('%H', pad(now.getUTCHours()));
+}
+
+function configure(dir, output) {
+  fs.mkdirSync(dir, { recursive: true });
+  return logger.setConfig({
+    prod: {
+      output: {
+        color: false,
+        format: 0, // Text
+        target: 2, // File
+        filePath: path.join(dir, 'app.log'),
+        ...output
+      },
+      fields: { time: false }
+    }
+  });
+}
+
+describe('File Rotation', () => {
+  afterAll(() => {
+    fs.rmSync(rootDir, { recursive: true, force: true });
+  });
+
+  test('should keep daily files in the directory of filePath', () => {
+    const dir = path.join(rootDir, 'daily', 'logs');
+    expect(configure(dir, { rotateDaily: true })).toBeTruthy();
+
+    logger.info('daily entry');
+    logger.shutdown();
+
+    const file = path.join(dir, today('app_%Y-%m-%d.log'));
+    expect(fs.readFileSync(file, 'utf8')).toContain('daily entry');
+    expect(fs.existsSync(today('app_%Y-%m-%d.log'))).toBe(false);
+  });
+
+  test('should name hourly files after the hour', () => {
+    const dir = path.join(rootDir, 'hourly');
+    expect(configure(dir, { rotateHourly: true })).toBeTruthy();
+
+    logger.info('hourly entry');
+    logger.shutdown();
+
+    expect(fs.readdirSync(dir)).toEqual([today('app_%Y-%m-%d_%H.log')]);
+  });
+
+  test('should split a period by size using the file pattern', () => {
+    const dir = path.join(rootDir, 'pattern');
+    expect(
+      configure(dir, { rotateDaily: true, filePattern: 'app-%Y-%m-%d.log', maxFileSize: 100 })
+    ).toBeTruthy();
+
+    for (let i = 0; i < 10; i++) {
+      logger.info(`entry number ${i} with some padding`);
+    }
+    logger.shutdown();
+
+    const base = today('app-%Y-%m-%d');
+    const files = fs.readdirSync(dir).sort();
+    expect(files).toContain(`${base}.log`);
+    expect(files).toContain(`${base}.1.log`);
+    expect(files).toContain(`${base}.2.log`);
+    expect(fs.readFileSync(path.join(dir, `${base}.log`), 'utf8')).toContain('entry number 0');
+  });
+
+  test('should delete the oldest files beyond maxBackups', () => {
+    const dir = path.join(rootDir, 'retention');
+    fs.mkdirSync(dir, { recursive: true });
+    const old = ['app-2020-01-01.log', 'app-2020-01-02.log', 'app-2020-01-02.1.log', 'other.log'];
+    old.forEach((name, i) => {
+      const file = path.join(dir, name);
+      fs.writeFileSync(file, 'old\n');
+      const time = new Date(Date.UTC(2020, 0, 1 + i));
+      fs.utimesSync(file, time, time);
+    });
+
+    expect(configure(dir, { rotateDaily: true, filePattern: 'app-%Y-%m-%d.log', maxBackups: 2 })).toBeTruthy();
+    logger.info('new entry');
+    logger.shutdown();
+
+    expect(fs.readdirSync(dir).sort()).toEqual(
+      ['app-2020-01-02.1.log', today('app-%Y-%m-%d.log'), 'other.log'].sort()
+    );
+  });
+
+  test('should reject invalid rotation settings', () => {
+    const dir = path.join(rootDir, 'invalid');
+    expect(() => configure(dir, { rotateDaily: true, rotateHourly: true })).toThrow(/accepts only one of/);
+    expect(() => configure(dir, { filePattern: 'app-%Y.log' })).toThrow(/output\.filePattern: requires/);
+    expect(() => configure(dir, { rotateDaily: true, filePattern: 'logs/app-%Y.log' })).toThrow(/output\.filePattern/);
+    expect(() => configure(dir, { rotateDaily: true, filePattern: 'app-%Q.log' })).toThrow(/output\.filePattern/);
+  });
+
+  test('should reject file patterns that reuse a name across periods', () => {
+    const dir = path.join(rootDir, 'ambiguous');
+    expect(() => configure(dir, { rotateDaily: true, filePattern: 'app.log' })).toThrow(
+      /output\.filePattern: 'app\.log' cannot tell daily files apart without %Y, %m, %d/
+    );
+    expect(() => configure(dir, { rotateDaily: true, filePattern: 'app-%m-%d.log' })).toThrow(
+      /cannot tell daily files apart without %Y$/m
+    );
+    expect(() => configure(dir, { rotateHourly: true, filePattern: 'app-%Y-%m-%d.log' })).toThrow(
+      /cannot tell hourly files apart without %H/
+    );
+    expect(() => configure(dir, { rotateEveryMinutes: 15, filePattern: 'app-%Y-%m-%d.log' })).toThrow(
+      /cannot tell 15-minute files apart without %H, %M/
+    );
+    expect(() => configure(dir, { rotateEveryMinutes: 15, filePattern: 'app-%Y-%m-%d_%H.log' })).toThrow(
+      /cannot tell 15-minute files apart without %M/
+    );
+
+    expect(configure(dir, { rotateDaily: true, filePattern: 'app-%Y.%j.log' })).toBeTruthy();
+    expect(configure(dir, { rotateHourly: true, filePattern: 'app-%Y%m%d%H.log' })).toBeTruthy();
+    expect(configure(dir, { rotateEveryMinutes: 120, filePattern: 'app-%Y-%m-%d_%H.log' })).toBeTruthy();
+    expect(configure(dir, { rotateEveryMinutes: 15, filePattern: 'app-%Y-%m-%d_%H-%M.log' })).toBeTruthy();
+    logger.shutdown();
+  });
+});
+
+describe('File Retention', () => {
+  const dir = path.join(rootDir, 'retention-policies');
+
+  function seed(files) {
+    fs.rmSync(dir, { recursive: true, force: true });
+    fs.mkdirSync(dir, { recursive: true });
+    const now = Date.now();
+    for (const [name, ageDays, size] of files) {
+      const file = path.join(dir, name);
+      fs.writeFileSync(file, 'x'.repeat(size));
+      const time = new Date(now - ageDays * 24 * 60 * 60 * 1000);
+      fs.utimesSync(file, time, time);
+    }
+  }
+
+  afterAll(() => {
+    fs.rmSync(rootDir, { recursive: true, force: true });
+  });
+
+  test('should delete time-rotated backups older than maxAgeDays', () => {
+    seed([
+      ['app-2020-01-01.log', 40, 10],
+      ['app-2020-01-02.log.gz', 35, 10],
+      ['app-2020-01-03.log', 2, 10],
+      ['app-2020-01-04.1.log.gz', 1, 10]
+    ]);
+
+    expect(
+      configure(dir, { rotateDaily: true, filePattern: 'app-%Y-%m-%d.log', maxAgeDays: 30 })
+    ).toBeTruthy();
+    logger.info('entry');
+    logger.shutdown();
+
+    expect(fs.readdirSync(dir).sort()).toEqual(
+      ['app-2020-01-03.log', 'app-2020-01-04.1.log.gz', today('app-%Y-%m-%d.log')].sort()
+    );
+  });
+
+  test('should delete the oldest size-rotated backups beyond maxTotalSize', () => {
+    seed([
+      ['app.log', 0, 150],
+      ['app.log.1', 1, 100],
+      ['app.log.2.gz', 2, 100],
+      ['app.log.3', 3, 100]
+    ]);
+
+    expect(configure(dir, { maxFileSize: 100, maxBackups: 5, maxTotalSize: 300 })).toBeTruthy();
+    logger.info('entry');
+    logger.shutdown();
+
+    // app.log was rotated to app.log.1 (150 bytes); with the new app.log only
+    // app.log.2 (the previous app.log.1) still fits under 300 bytes.
+    expect(fs.readdirSync(dir).sort()).toEqual(['app.log', 'app.log.1', 'app.log.2']);
+  });
+
+  test('should reject non-positive retention limits', () => {
+    expect(() => configure(dir, { maxAgeDays: 0 })).toThrow(/output\.maxAgeDays/);
+    expect(() => configure(dir, { maxTotalSize: -1 })).toThrow(/output\.maxTotalSize/);
+  });
+});
+
+describe('Rotation Schedules', () => {
+  const pad = (n) => String(n).padStart(2, '0');
+
+  afterAll(() => {
+    fs.rmSync(rootDir, { recursive: true, force: true });
+  });
+
+  function writeOne(dir, output) {
+    fs.rmSync(dir, { recursive: true, force: true });
+    expect(configure(dir, output)).toBeTruthy();
+    logger.info('scheduled entry');
+    logger.shutdown();
+    return fs.readdirSync(dir);
+  }
+
+  test('should name interval files after the start of the interval', () => {
+    const now = new Date();
+    const minute = Math.floor(now.getUTCMinutes() / 15) * 15;
+    const expected =
+      `app_${now.getUTCFullYear()}-${pad(now.getUTCMonth() + 1)}-${pad(now.getUTCDate())}` +
+      `_${pad(now.getUTCHours())}-${pad(minute)}.log`;
+
+    expect(writeOne(path.join(rootDir, 'minutes'), { rotateEveryMinutes: 15 })).toEqual([expected]);
+  });
+
+  test('should name weekly files after the Monday of the week', () => {
+    const now = new Date();
+    const monday = new Date(
+      Date.UTC(now.getUTCFullYear(), now.getUTCMonth(), now.getUTCDate() - ((now.getUTCDay() + 6) % 7))
+    );
+    const expected = `app_${monday.getUTCFullYear()}-${pad(monday.getUTCMonth() + 1)}-${pad(monday.getUTCDate())}.log`;
+
+    expect(writeOne(path.join(rootDir, 'weekly'), { rotateWeekly: true })).toEqual([expected]);
+  });
+
+  test('should use local time when configured', () => {
+    const now = new Date();
+    const expected = `app_${now.getFullYear()}-${pad(now.getMonth() + 1)}-${pad(now.getDate())}_${pad(now.getHours())}.log`;
+
+    expect(
+      writeOne(path.join(rootDir, 'local'), { rotateHourly: true, rotationTimezone: 'local' })
+    ).toEqual([expected]);
+  });
+
+  test('should reject conflicting or out-of-range schedules', () => {
+    const dir = path.join(rootDir, 'invalid-schedule');
+    expect(() => configure(dir, { rotateDaily: true, rotateWeekly: true })).toThrow(/accepts only one of/);
+    expect(() => configure(dir, { rotateEveryMinutes: 0 })).toThrow(/output\.rotateEveryMinutes/);
+    expect(() => configure(dir, { rotateEveryMinutes: 1441 })).toThrow(/output\.rotateEveryMinutes/);
+  });
+});
@@ -0,0 +1,141 @@
+import * as logger from '../index.js';
+import fs from 'fs';
+
+const logFile = './test.runtime-levels.log';
+
+function configure(extra) {
+  return logger.setConfig({
+    prod: {
+      output: {
+        color: false,
+        format: 1, // JSON
+        target: 2, // File
+        filePath: logFile
+      },
+      level: 'info',
+      ...extra
+    }
+  });
+}
+
+function loggedMessages() {
+  return fs
+    .readFileSync(logFile, 'utf8')
+    .trim()
+    .split('\n')
+    .filter(Boolean)
+    .map((line) => JSON.parse(line).msg)
+    .map((msg) => (typeof msg === 'string' ? msg : msg.message));
+}
+
+describe('Runtime Levels', () => {
+  beforeEach(() => {
+    fs.writeFileSync(logFile, '');
+  });
+
+  afterAll(() => {
+    try {
+      if (fs.existsSync(logFile)) {
+        fs.unlinkSync(logFile);
+      }
+    } catch (err) {
+      // Ignore cleanup errors
+    }
+  });
+
+  test('should change the default and per-scope levels', () => {
+    expect(configure({})).toBeTruthy();
+    expect(logger.getLevel()).toBe('Info');
+
+    logger.debug('debug before');
+    logger.setLevel('debug');
+    logger.debug('debug after');
+
+    logger.setLevel('warn', 'Db*');
+    logger.info({ scope: 'DbPool', message: 'db info' });
+    logger.warn({ scope: 'DbPool', message: 'db warn' });
+    logger.shutdown();
+
+    expect(logger.getLevel()).toBe('Debug');
+    expect(logger.getLevel('DbPool')).toBe('Warn');
+    expect(logger.getLevel('Auth')).toBe('Debug');
+    expect(loggedMessages()).toEqual(['debug after', 'db warn']);
+  });
+
+  test('should reject unknown levels', () => {
+    expect(configure({})).toBeTruthy();
+    expect(() => logger.setLevel('loud')).toThrow(/loud/);
+    expect(logger.getLevel()).toBe('Info');
+  });
+
+  test('should report no minimum when none is configured', () => {
+    expect(configure({ level: undefined })).toBeTruthy();
+    expect(logger.getLevel()).toBeNull();
+  });
+
+  test('should toggle debug and restore the configured levels', () => {
+    expect(configure({ level: 'warn', levels: 'Audit=error' })).toBeTruthy();
+
+    expect(logger.toggleDebugLevel()).toBe(true);
+    expect(logger.getLevel()).toBe('Debug');
+    expect(logger.getLevel('Audit')).toBe('Debug');
+    logger.debug('while debugging');
+
+    expect(logger.toggleDebugLevel()).toBe(false);
+    expect(logger.getLevel('Audit')).toBe('Error');
+    logger.debug('after debugging');
+    logger.shutdown();
+
+    expect(loggedMessages()).toEqual(['while debugging']);
+  });
+
+  test('should keep scopes that are turned off silent while debug is on', () => {
+    expect(configure({ levels: 'Healthcheck=off' })).toBeTruthy();
+
+    expect(logger.toggleDebugLevel()).toBe(true);
+    expect(logger.getLevel('Healthcheck')).toBe('off');
+    logger.error({ scope: 'Healthcheck', message: 'probe failed' });
+    logger.debug({ scope: 'Api', message: 'api debug' });
+
+    expect(logger.toggleDebugLevel()).toBe(false);
+    logger.shutdown();
+
+    expect(loggedMessages()).toEqual(['api debug']);
+  });
+
+  test('should keep setLevel() calls made while debug is on', () => {
+    expect(configure({ level: 'warn' })).toBeTruthy();
+
+    expect(logger.toggleDebugLevel()).toBe(true);
+    logger.setLevel('error', 'Audit');
+    logger.setLevel('trace', 'Db');
+    expect(logger.getLevel('Audit')).toBe('Debug');
+    expect(logger.getLevel('Db')).toBe('Trace');
+    logger.debug({ scope: 'Audit', message: 'audit while debugging' });
+
+    expect(logger.toggleDebugLevel()).toBe(false);
+    expect(logger.getLevel()).toBe('Warn');
+    expect(logger.getLevel('Audit')).toBe('Error');
+    expect(logger.getLevel('Db')).toBe('Trace');
+    logger.warn({ scope: 'Audit', message: 'audit warning' });
+    logger.trace({ scope: 'Db', message: 'db trace' });
+    logger.shutdown();
+
+    expect(loggedMessages()).toEqual(['audit while debugging', 'db trace']);
+  });
+
+  test('should toggle debug on SIGUSR2 when enabled', async () => {
+    expect(configure({ debugSignal: true })).toBeTruthy();
+
+    process.kill(process.pid, 'SIGUSR2');
+    await new Promise((resolve) => setTimeout(resolve, 50));
+    expect(logger.getLevel()).toBe('Debug');
+
+    process.kill(process.pid, 'SIGUSR2');
+    await new Promise((resolve) => setTimeout(resolve, 50));
+    expect(logger.getLevel()).toBe('Info');
+
+    expect(configure({ debugSignal: false })).toBeTruthy();
+    expect(process.listenerCount('SIGUSR2')).toBe(0);
+  });
+});
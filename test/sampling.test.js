@@ -0,0 +1,161 @@
+import * as logger from '../index.js';
+import fs from 'fs';
+
+const logFile = './test.sampling.log';
+
+function configure(sampling) {
+  return logger.setConfig({
+    prod: {
+      output: {
+        color: false,
+        format: 1, // JSON
+        target: 2, // File
+        filePath: logFile
+      },
+      fields: { level: true },
+      sampling
+    }
+  });
+}
+
+function readEntries() {
+  return fs
+    .readFileSync(logFile, 'utf8')
+    .trim()
+    .split('\n')
+    .filter(Boolean)
+    .map((line) => JSON.parse(line));
+}
+
+describe('Sampling and Rate Limiting', () => {
+  beforeEach(() => {
+    fs.writeFileSync(logFile, '');
+  });
+
+  afterAll(() => {
+    try {
+      if (fs.existsSync(logFile)) {
+        fs.unlinkSync(logFile);
+      }
+    } catch (err) {
+      // Ignore cleanup errors
+    }
+  });
+
+  test('should sample entries per level and per scope', () => {
+    expect(configure({ sample: { debug: 0, info: 0.5 }, scopes: { 'Noisy*': 0 } })).toBeTruthy();
+
+    for (let i = 0; i < 1000; i++) {
+      logger.debug(`debug ${i}`);
+      logger.info(`info ${i}`);
+      logger.warn({ scope: 'NoisyWorker', message: `noisy ${i}` });
+    }
+    logger.warn('kept');
+    logger.shutdown();
+
+    const entries = readEntries();
+    const infos = entries.filter((entry) => entry.level === 'Info').length;
+    expect(entries.some((entry) => entry.level === 'Debug')).toBe(false);
+    expect(entries.some((entry) => entry.scope === 'NoisyWorker')).toBe(false);
+    expect(infos).toBeGreaterThan(350);
+    expect(infos).toBeLessThan(650);
+    expect(entries.at(-1).msg).toBe('kept');
+  });
+
+  test('should rate-limit each scope and report dropped entries', async () => {
+    expect(configure({ maxPerSecond: 5 })).toBeTruthy();
+
+    for (let i = 0; i < 20; i++) {
+      logger.info({ scope: 'Hot', message: `hot ${i}` });
+    }
+    logger.info({ scope: 'Quiet', message: 'quiet' });
+
+    await new Promise((resolve) => setTimeout(resolve, 300));
+    logger.info({ scope: 'Hot', message: 'after refill' });
+    logger.shutdown();
+
+    const entries = readEntries();
+    const hot = entries
+      .filter((entry) => entry.scope === 'Hot')
+      .map((entry) => [entry.level, entry.msg.message]);
+    expect(hot).toEqual([
+      ['Info', 'hot 0'],
+      ['Info', 'hot 1'],
+      ['Info', 'hot 2'],
+      ['Info', 'hot 3'],
+      ['Info', 'hot 4'],
+      ['Warn', 'rate limit exceeded: 15 entries dropped'],
+      ['Info', 'after refill']
+    ]);
+    expect(entries.some((entry) => entry.scope === 'Quiet')).toBe(true);
+  });
+
+  test('should collapse identical entries within the dedupe window', async () => {
+    expect(configure({ dedupeWindowMs: 100 })).toBeTruthy();
+
+    for (let i = 0; i < 50; i++) {
+      logger.warn('Disk almost full');
+    }
+    logger.warn('Different message');
+
+    await new Promise((resolve) => setTimeout(resolve, 150));
+    logger.warn('Disk almost full');
+    for (let i = 0; i < 3; i++) {
+      logger.error({ scope: 'Db', message: 'Connection lost' });
+    }
+    logger.shutdown();
+
+    expect(readEntries().map((entry) => [entry.level, entry.scope, entry.msg])).toEqual([
+      ['Warn', undefined, 'Disk almost full'],
+      ['Warn', undefined, 'Different message'],
+      ['Warn', undefined, 'message repeated 49 times: Disk almost full'],
+      ['Warn', undefined, 'Disk almost full'],
+      ['Error', 'Db', { message: 'Connection lost' }],
+      ['Error', 'Db', { message: 'message repeated 2 times: Connection lost' }]
+    ]);
+  });
+
+  test('should write summaries on a timer when nothing else is logged', async () => {
+    expect(configure({ maxPerSecond: 5, dedupeWindowMs: 50 })).toBeTruthy();
+
+    for (let i = 0; i < 3; i++) {
+      logger.warn('Disk almost full');
+    }
+    for (let i = 0; i < 8; i++) {
+      logger.info({ scope: 'Hot', message: `hot ${i}` });
+    }
+
+    await new Promise((resolve) => setTimeout(resolve, 400));
+
+    const messages = readEntries().map((entry) => entry.msg.message ?? entry.msg);
+    expect(messages).toContain('message repeated 2 times: Disk almost full');
+    expect(messages).toContain('rate limit exceeded: 3 entries dropped');
+    logger.shutdown();
+  });
+
+  test('should not dedupe entries dropped by the rate limit', async () => {
+    expect(configure({ maxPerSecond: 2, dedupeWindowMs: 5000 })).toBeTruthy();
+
+    logger.info('first');
+    logger.info('second');
+    logger.info('dropped');
+
+    await new Promise((resolve) => setTimeout(resolve, 600));
+    logger.info('dropped');
+    logger.shutdown();
+
+    expect(readEntries().map((entry) => entry.msg)).toEqual([
+      'first',
+      'second',
+      'rate limit exceeded: 1 entries dropped',
+      'dropped'
+    ]);
+  });
+
+  test('should reject invalid sampling settings', () => {
+    expect(() => configure({ sample: { debug: 2 } })).toThrow(/sampling\.sample\.debug: must be between 0 and 1/);
+    expect(() => configure({ sample: { loud: 0.5 } })).toThrow(/sampling\.sample\.loud/);
+    expect(() => configure({ maxPerSecond: 0 })).toThrow(/sampling\.maxPerSecond/);
+    expect(() => configure({ dedupeWindowMs: 0 })).toThrow(/sampling\.dedupeWindowMs/);
+  });
+});
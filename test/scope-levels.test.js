@@ -0,0 +1,90 @@
+import * as logger from '../index.js';
+import fs from 'fs';
+
+const logFile = './test.scope-levels.log';
+
+function configure(extra) {
+  return logger.setConfig({
+    prod: {
+      output: {
+        color: false,
+        format: 1, // JSON
+        target: 2, // File
+        filePath: logFile
+      },
+      ...extra
+    }
+  });
+}
+
+function loggedMessages() {
+  return fs
+    .readFileSync(logFile, 'utf8')
+    .trim()
+    .split('\n')
+    .filter(Boolean)
+    .map((line) => JSON.parse(line).msg)
+    .map((msg) => (typeof msg === 'string' ? msg : msg.message));
+}
+
+describe('Per-scope Levels', () => {
+  beforeEach(() => {
+    fs.writeFileSync(logFile, '');
+    delete process.env.EVENTUM_LOG;
+  });
+
+  afterAll(() => {
+    delete process.env.EVENTUM_LOG;
+    try {
+      if (fs.existsSync(logFile)) {
+        fs.unlinkSync(logFile);
+      }
+    } catch (err) {
+      // Ignore cleanup errors
+    }
+  });
+
+  test('should apply directives by scope with a default level', () => {
+    expect(configure({ levels: 'info,AuthService=debug,Payment*=trace,Healthcheck=off' })).toBeTruthy();
+
+    logger.debug({ scope: 'AuthService', message: 'auth debug' });
+    logger.trace({ scope: 'AuthService', message: 'auth trace' });
+    logger.trace({ scope: 'PaymentGateway', message: 'payment trace' });
+    logger.debug({ scope: 'Orders', message: 'orders debug' });
+    logger.info({ scope: 'Orders', message: 'orders info' });
+    logger.debug('unscoped debug');
+    logger.fatal({ scope: 'Healthcheck', message: 'healthcheck fatal' });
+    logger.shutdown();
+
+    expect(loggedMessages()).toEqual(['auth debug', 'payment trace', 'orders info']);
+  });
+
+  test('should prefer exact scopes and longer patterns', () => {
+    expect(configure({ level: 'error', levels: 'Pay*=warn,Payment*=debug,Payments=error' })).toBeTruthy();
+
+    logger.debug({ scope: 'PaymentGateway', message: 'gateway debug' });
+    logger.info({ scope: 'Payroll', message: 'payroll info' });
+    logger.warn({ scope: 'Payroll', message: 'payroll warn' });
+    logger.warn({ scope: 'Payments', message: 'payments warn' });
+    logger.warn('unscoped warn');
+    logger.shutdown();
+
+    expect(loggedMessages()).toEqual(['gateway debug', 'payroll warn']);
+  });
+
+  test('should let EVENTUM_LOG replace the configured directives', () => {
+    process.env.EVENTUM_LOG = 'warn,Db=trace';
+    expect(configure({ levels: 'trace' })).toBeTruthy();
+
+    logger.info('default info');
+    logger.trace({ scope: 'Db', message: 'db trace' });
+    logger.shutdown();
+
+    expect(loggedMessages()).toEqual(['db trace']);
+  });
+
+  test('should reject directives with unknown levels', () => {
+    expect(() => configure({ levels: 'info,Auth=loud' })).toThrow(/\.levels: /);
+    expect(() => configure({ levels: '=debug' })).toThrow(/\.levels: /);
+  });
+});
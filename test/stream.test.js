@@ -0,0 +1,259 @@
+import * as logger from '../index.js';
+import fs from 'fs';
+import net from 'net';
+import os from 'os';
+import path from 'path';
+
+function waitFor(check, timeout = 3000) {
+  return new Promise((resolve, reject) => {
+    const started = Date.now();
+    const timer = setInterval(() => {
+      if (check()) {
+        clearInterval(timer);
+        resolve();
+      } else if (Date.now() - started > timeout) {
+        clearInterval(timer);
+        reject(new Error('Timed out waiting for stream data'));
+      }
+    }, 20);
+  });
+}
+
+function listen(server, ...args) {
+  return new Promise((resolve) => server.listen(...args, resolve));
+}
+
+describe('Stream Targets', () => {
+  let received = '';
+  const sockets = new Set();
+
+  function createServer() {
+    const server = net.createServer((socket) => {
+      sockets.add(socket);
+      socket.on('data', (chunk) => {
+        received += chunk.toString();
+      });
+      socket.on('close', () => sockets.delete(socket));
+    });
+    return server;
+  }
+
+  beforeEach(() => {
+    received = '';
+  });
+
+  afterAll(() => {
+    logger.shutdown();
+  });
+
+  test('should stream newline-delimited JSON over TCP', async () => {
+    const server = createServer();
+    await listen(server, 0, '127.0.0.1');
+
+    const ok = logger.setConfig({
+      prod: {
+        output: {
+          format: 1, // JSON
+          target: 6, // Tcp
+          stream: { address: `127.0.0.1:${server.address().port}` }
+        },
+        fields: { time: false, msg: true, level: true }
+      }
+    });
+    expect(ok).toBeTruthy();
+
+    logger.info('Order placed');
+    logger.error('Payment failed');
+
+    await waitFor(() => received.split('\n').length > 2);
+
+    expect(received).toBe(
+      '{"level":"Info","msg":"Order placed"}\n{"level":"Error","msg":"Payment failed"}\n'
+    );
+
+    sockets.forEach((socket) => socket.destroy());
+    await new Promise((resolve) => server.close(resolve));
+  });
+
+  test.skipIf(process.platform === 'win32')(
+    'should spill entries while the socket is down and replay them on reconnect',
+    async () => {
+      const socketPath = path.join(os.tmpdir(), `eventum-stream-${process.pid}.sock`);
+      fs.rmSync(socketPath, { force: true });
+
+      const ok = logger.setConfig({
+        prod: {
+          output: {
+            format: 0, // Text
+            target: 7, // UnixSocket
+            stream: { address: socketPath, reconnectBaseMs: 10, reconnectMaxMs: 20 }
+          },
+          fields: { time: false, msg: true, level: true }
+        }
+      });
+      expect(ok).toBeTruthy();
+
+      logger.warn('Collector not started yet');
+
+      const server = createServer();
+      await listen(server, socketPath);
+      await new Promise((resolve) => setTimeout(resolve, 50));
+
+      logger.info('Collector is up');
+
+      await waitFor(() => received.includes('Collector is up'));
+
+      expect(received).toBe('[Warn] Collector not started yet\n[Info] Collector is up\n');
+
+      sockets.forEach((socket) => socket.destroy());
+      await new Promise((resolve) => server.close(resolve));
+      fs.rmSync(socketPath, { force: true });
+    }
+  );
+
+  test.skipIf(process.platform === 'win32')(
+    'should deliver spilled entries to the previous address when it changes',
+    async () => {
+      const socketPath = path.join(os.tmpdir(), `eventum-stream-${process.pid}-old.sock`);
+      fs.rmSync(socketPath, { force: true });
+
+      const ok = logger.setConfig({
+        prod: {
+          output: {
+            format: 0, // Text
+            target: 7, // UnixSocket
+            stream: { address: socketPath, reconnectBaseMs: 60000, reconnectMaxMs: 60000 }
+          },
+          fields: { time: false, msg: true, level: true }
+        }
+      });
+      expect(ok).toBeTruthy();
+
+      logger.warn('Spilled for the old collector');
+      await new Promise((resolve) => setTimeout(resolve, 200));
+
+      // The reconnect backoff is far away, so only the address change replays it.
+      const oldServer = createServer();
+      await listen(oldServer, socketPath);
+      const newServer = createServer();
+      await listen(newServer, 0, '127.0.0.1');
+
+      expect(
+        logger.setConfig({
+          prod: {
+            output: {
+              format: 0, // Text
+              target: 6, // Tcp
+              stream: { address: `127.0.0.1:${newServer.address().port}` }
+            },
+            fields: { time: false, msg: true, level: true }
+          }
+        })
+      ).toBeTruthy();
+      logger.info('Sent to the new collector');
+
+      await waitFor(
+        () =>
+          received.includes('Spilled for the old collector') &&
+          received.includes('Sent to the new collector')
+      );
+
+      sockets.forEach((socket) => socket.destroy());
+      await new Promise((resolve) => oldServer.close(resolve));
+      await new Promise((resolve) => newServer.close(resolve));
+      fs.rmSync(socketPath, { force: true });
+    }
+  );
+
+  test('should keep each text entry on one line', async () => {
+    const server = createServer();
+    await listen(server, 0, '127.0.0.1');
+
+    const ok = logger.setConfig({
+      prod: {
+        output: {
+          format: 0, // Text
+          target: 6, // Tcp
+          stream: { address: `127.0.0.1:${server.address().port}` }
+        },
+        fields: { time: false, msg: true, level: true }
+      }
+    });
+    expect(ok).toBeTruthy();
+
+    logger.info({ scope: 'Orders', orderId: 42, items: ['book', 'pen'] });
+    logger.error('Payment failed\n    at charge (billing.js:10)');
+
+    await waitFor(() => received.split('\n').length > 2);
+
+    expect(received).toBe(
+      '[Info] [Orders] {"items":["book","pen"],"orderId":42}\n' +
+        '[Error] Payment failed\\n    at charge (billing.js:10)\n'
+    );
+
+    sockets.forEach((socket) => socket.destroy());
+    await new Promise((resolve) => server.close(resolve));
+  });
+
+  test('should not block the logging thread on a stalled collector', async () => {
+    const server = net.createServer((socket) => {
+      sockets.add(socket);
+      socket.pause();
+      socket.on('close', () => sockets.delete(socket));
+    });
+    await listen(server, 0, '127.0.0.1');
+
+    const ok = logger.setConfig({
+      prod: {
+        output: {
+          format: 1, // JSON
+          target: 6, // Tcp
+          stream: { address: `127.0.0.1:${server.address().port}`, connectTimeoutMs: 1000 }
+        }
+      }
+    });
+    expect(ok).toBeTruthy();
+
+    // Far more than the socket buffers hold, so a blocking write would wait
+    // for the write timeout.
+    const payload = 'x'.repeat(64 * 1024);
+    const started = Date.now();
+    for (let i = 0; i < 200; i++) {
+      logger.info(payload);
+    }
+    expect(Date.now() - started).toBeLessThan(500);
+
+    logger.shutdown();
+    sockets.forEach((socket) => socket.destroy());
+    await new Promise((resolve) => server.close(resolve));
+  }, 15000);
+
+  test('should require an address', () => {
+    const configure = () =>
+      logger.setConfig({
+        prod: {
+          output: {
+            format: 1,
+            target: 6
+          }
+        }
+      });
+
+    expect(configure).toThrow(/prod\.output\.stream\.address: must be set/);
+  });
+
+  test('should reject TLS on Unix sockets', () => {
+    const configure = () =>
+      logger.setConfig({
+        prod: {
+          output: {
+            format: 1,
+            target: 7,
+            stream: { address: '/tmp/collector.sock', tls: true }
+          }
+        }
+      });
+
+    expect(configure).toThrow(/prod\.output\.stream\.tls: is only supported with the Tcp target/);
+  });
+});
@@ -0,0 +1,164 @@
+import * as logger from '../index.js';
+import dgram from 'dgram';
+import net from 'net';
+
+function waitFor(check, timeout = 2000) {
+  return new Promise((resolve, reject) => {
+    const started = Date.now();
+    const timer = setInterval(() => {
+      if (check()) {
+        clearInterval(timer);
+        resolve();
+      } else if (Date.now() - started > timeout) {
+        clearInterval(timer);
+        reject(new Error('Timed out waiting for syslog messages'));
+      }
+    }, 20);
+  });
+}
+
+describe('Syslog Target', () => {
+  let udpServer;
+  let tcpServer;
+  const udpMessages = [];
+  let tcpData = '';
+
+  beforeAll(async () => {
+    udpServer = dgram.createSocket('udp4');
+    udpServer.on('message', (msg) => udpMessages.push(msg.toString()));
+    await new Promise((resolve) => udpServer.bind(0, '127.0.0.1', resolve));
+
+    tcpServer = net.createServer((socket) => {
+      socket.on('data', (chunk) => {
+        tcpData += chunk.toString();
+      });
+    });
+    await new Promise((resolve) => tcpServer.listen(0, '127.0.0.1', resolve));
+  });
+
+  afterAll(() => {
+    logger.shutdown();
+    udpServer.close();
+    tcpServer.close();
+  });
+
+  test('should send RFC 5424 messages over UDP', async () => {
+    const ok = logger.setConfig({
+      prod: {
+        output: {
+          format: 1, // JSON
+          target: 4, // Syslog
+          syslog: {
+            protocol: 'udp',
+            address: `127.0.0.1:${udpServer.address().port}`,
+            facility: 'local0',
+            appName: 'checkout',
+            msgid: 'HTTP'
+          }
+        },
+        fields: { time: false, msg: true, level: true }
+      }
+    });
+    expect(ok).toBeTruthy();
+
+    logger.info('Order placed');
+    logger.error('Payment failed');
+
+    await waitFor(() => udpMessages.length >= 2);
+
+    // local0 (16) * 8 + informational (6) = 134, + error (3) = 131
+    expect(udpMessages[0]).toMatch(
+      /^<134>1 \d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}\.\d{3}Z \S+ checkout \d+ HTTP - /
+    );
+    expect(udpMessages[0]).toContain('{"level":"Info","msg":"Order placed"}');
+    expect(udpMessages[1].startsWith('<131>1 ')).toBe(true);
+  });
+
+  test('should send octet-counted RFC 3164 messages over TCP from the batch thread', async () => {
+    const ok = logger.setConfig({
+      prod: {
+        output: {
+          format: 0, // Text
+          target: 4, // Syslog
+          batchEnabled: true,
+          batchIntervalMs: 20,
+          syslog: {
+            protocol: 'tcp',
+            format: 'rfc3164',
+            address: `127.0.0.1:${tcpServer.address().port}`
+          }
+        },
+        fields: { time: false, msg: true, level: true }
+      }
+    });
+    expect(ok).toBeTruthy();
+
+    logger.warn('Disk almost full');
+    logger.shutdown();
+
+    await waitFor(() => tcpData.length > 0);
+
+    const [length, ...rest] = tcpData.split(' ');
+    const frame = rest.join(' ');
+    expect(Number(length)).toBe(Buffer.byteLength(frame));
+    // user (1) * 8 + warning (4) = 12
+    expect(frame).toMatch(/^<12>\w{3} [ \d]\d \d{2}:\d{2}:\d{2} \S+ node\[\d+\]: \[Warn\] Disk almost full$/);
+  });
+
+  test('should not block the logging thread on a stalled TCP collector', async () => {
+    const sockets = [];
+    let received = 0;
+    const server = net.createServer((socket) => {
+      sockets.push(socket);
+      socket.pause();
+      socket.on('data', (chunk) => {
+        received += chunk.length;
+      });
+    });
+    await new Promise((resolve) => server.listen(0, '127.0.0.1', resolve));
+
+    const ok = logger.setConfig({
+      prod: {
+        output: {
+          format: 0, // Text
+          target: 4, // Syslog
+          syslog: { protocol: 'tcp', address: `127.0.0.1:${server.address().port}` }
+        },
+        fields: { time: false, msg: true, level: false }
+      }
+    });
+    expect(ok).toBeTruthy();
+
+    // Far more than the socket buffers hold, so a blocking write would wait
+    // for the collector.
+    const payload = 'x'.repeat(64 * 1024);
+    const started = Date.now();
+    for (let i = 0; i < 200; i++) {
+      logger.info(payload);
+    }
+    expect(Date.now() - started).toBeLessThan(500);
+
+    await waitFor(() => sockets.length > 0);
+    sockets.forEach((socket) => socket.resume());
+    await waitFor(() => received >= 200 * payload.length);
+
+    logger.shutdown();
+    sockets.forEach((socket) => socket.destroy());
+    await new Promise((resolve) => server.close(resolve));
+  }, 15000);
+
+  test('should reject unknown facilities', () => {
+    const configure = () =>
+      logger.setConfig({
+        prod: {
+          output: {
+            format: 1,
+            target: 4,
+            syslog: { facility: 'local9' }
+          }
+        }
+      });
+
+    expect(configure).toThrow(/prod\.output\.syslog\.facility: 'local9' is not a known syslog facility/);
+  });
+});
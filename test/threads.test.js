@@ -0,0 +1,88 @@
+import * as logger from '../index.js';
+import { Worker } from 'worker_threads';
+import fs from 'fs';
+
+const logFile = './test.threads.log';
+
+const workerSource = `
+  const { workerData } = require('worker_threads');
+  const logger = require(workerData.entry);
+  logger.setThreadName('worker-' + workerData.index);
+  logger.info('from worker ' + workerData.index);
+`;
+
+function runWorker(index) {
+  return new Promise((resolve, reject) => {
+    const worker = new Worker(workerSource, {
+      eval: true,
+      workerData: { entry: new URL('../index.cjs', import.meta.url).pathname, index }
+    });
+    worker.on('error', reject);
+    worker.on('exit', resolve);
+  });
+}
+
+describe('Thread Identity', () => {
+  beforeAll(() => {
+    const config = {
+      prod: {
+        output: {
+          color: false,
+          format: 1, // JSON
+          target: 2, // File
+          filePath: logFile,
+          batchEnabled: true
+        },
+        fields: {
+          time: false,
+          msg: true,
+          level: true,
+          threadId: true,
+          threadName: true
+        }
+      }
+    };
+
+    const ok = logger.setConfig(config);
+    if (!ok) {
+      throw new Error('Logger rejected config');
+    }
+  });
+
+  afterAll(() => {
+    try {
+      if (fs.existsSync(logFile)) {
+        fs.unlinkSync(logFile);
+      }
+    } catch (err) {
+      // Ignore cleanup errors
+    }
+  });
+
+  test('should tag entries with the worker thread identity', async () => {
+    logger.setThreadName('main');
+    logger.info('from main');
+
+    await Promise.all([1, 2, 3].map(runWorker));
+    logger.shutdown();
+
+    const lines = fs
+      .readFileSync(logFile, 'utf8')
+      .trim()
+      .split('\n')
+      .map((line) => JSON.parse(line));
+
+    expect(lines.length).toBe(4);
+
+    const main = lines.find((entry) => entry.msg === 'from main');
+    expect(main.threadId).toBe(0);
+    expect(main.threadName).toBe('main');
+
+    const workers = lines.filter((entry) => entry.msg.startsWith('from worker'));
+    expect(new Set(workers.map((entry) => entry.threadId)).size).toBe(3);
+    for (const entry of workers) {
+      expect(entry.threadId).toBeGreaterThan(0);
+      expect(entry.threadName).toBe(`worker-${entry.msg.split(' ').pop()}`);
+    }
+  });
+});
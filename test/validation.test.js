@@ -0,0 +1,166 @@
+import * as logger from '../index.js';
+import fs from 'fs';
+import path from 'path';
+
+const rootDir = './test.validation';
+// Permission bits do not stop root, and Windows has none.
+const permissionsApply = test.skipIf(process.platform === 'win32' || process.getuid?.() === 0);
+
+function output(fields) {
+  return { prod: { output: { format: logger.OutputFormat.Json, ...fields } } };
+}
+
+function paths(issues) {
+  return issues.map((issue) => issue.path);
+}
+
+describe('Config Validation', () => {
+  beforeEach(() => {
+    fs.rmSync(rootDir, { recursive: true, force: true });
+    fs.mkdirSync(rootDir, { recursive: true });
+  });
+
+  afterAll(() => {
+    logger.shutdown();
+    fs.rmSync(rootDir, { recursive: true, force: true });
+  });
+
+  test('should report every error with the path of its field', () => {
+    const result = logger.validateConfig(
+      output({
+        target: logger.OutputTarget.File,
+        filePath: path.join(rootDir, 'app.log'),
+        batchSize: -1,
+        batchIntervalMs: 0,
+        maxFileSize: 0,
+        maxBackups: 0
+      })
+    );
+
+    expect(result.valid).toBe(false);
+    expect(paths(result.errors).sort()).toEqual([
+      'prod.output.batchIntervalMs',
+      'prod.output.batchSize',
+      'prod.output.maxBackups',
+      'prod.output.maxFileSize'
+    ]);
+    expect(result.errors.find((issue) => issue.path === 'prod.output.batchSize').message).toBe(
+      'must be greater than 0'
+    );
+  });
+
+  test('should check every color of the theme', () => {
+    const result = logger.validateConfig(
+      output({
+        target: logger.OutputTarget.Stdout,
+        theme: { info: 'bright green', warn: 'orange', key: 'bold purplish', value: 'dimmed' }
+      })
+    );
+
+    expect(result.valid).toBe(false);
+    expect(result.errors).toEqual([
+      { path: 'prod.output.theme.warn', message: "is invalid: Unknown color 'orange'" },
+      { path: 'prod.output.theme.key', message: "is invalid: Unknown color 'purplish'" }
+    ]);
+  });
+
+  test('should throw a ConfigValidationError from setConfig', () => {
+    let thrown;
+    try {
+      logger.setConfig(output({ target: logger.OutputTarget.Stdout, batchSize: 0, filePath: 'unused.log' }));
+    } catch (err) {
+      thrown = err;
+    }
+
+    expect(thrown).toBeInstanceOf(logger.ConfigValidationError);
+    expect(thrown).toBeInstanceOf(Error);
+    expect(thrown.name).toBe('ConfigValidationError');
+    expect(thrown.code).toBe('ERR_INVALID_CONFIG');
+    expect(thrown.message).toContain('prod.output.batchSize: must be greater than 0');
+    expect(paths(thrown.errors)).toEqual(['prod.output.batchSize']);
+    expect(paths(thrown.warnings)).toEqual(['prod.output.filePath']);
+  });
+
+  test('should warn about settings for sinks that are not in the chain', () => {
+    const config = output({
+      target: logger.OutputTarget.Stdout,
+      filePath: 'unused.log',
+      http: { url: 'http://localhost:1' }
+    });
+
+    const result = logger.validateConfig(config);
+    expect(result.valid).toBe(true);
+    expect(result.errors).toEqual([]);
+    expect(result.warnings).toEqual([
+      { path: 'prod.output.filePath', message: 'is ignored without the File target' },
+      { path: 'prod.output.http', message: 'is ignored without the Http target' }
+    ]);
+    expect(logger.setConfig(config).output.target).toBe(logger.OutputTarget.Stdout);
+  });
+
+  test('should reject a filePath that cannot be written', () => {
+    const blocker = path.join(rootDir, 'not-a-dir');
+    fs.writeFileSync(blocker, '');
+
+    const result = logger.validateConfig(
+      output({ target: logger.OutputTarget.File, filePath: path.join(blocker, 'logs', 'app.log') })
+    );
+    expect(result.errors).toEqual([
+      { path: 'prod.output.filePath', message: `'${blocker}' is not a directory` }
+    ]);
+
+    const dir = logger.validateConfig(output({ target: logger.OutputTarget.File, filePath: rootDir }));
+    expect(dir.errors[0].message).toBe(`'${rootDir}' is a directory`);
+  });
+
+  permissionsApply('should reject a read-only log directory', () => {
+    const dir = path.join(rootDir, 'read-only');
+    fs.mkdirSync(dir, { mode: 0o500 });
+    try {
+      const result = logger.validateConfig(
+        output({ target: logger.OutputTarget.File, filePath: path.join(dir, 'app.log') })
+      );
+      expect(result.errors[0].message).toBe(`directory '${dir}' is not writable`);
+    } finally {
+      fs.chmodSync(dir, 0o700);
+    }
+  });
+
+  test('should not apply a config it validates', () => {
+    const filePath = path.join(rootDir, 'applied.log');
+    logger.setConfig(output({ target: logger.OutputTarget.File, filePath, batchEnabled: false }));
+
+    const other = path.join(rootDir, 'checked.log');
+    expect(logger.validateConfig(output({ target: logger.OutputTarget.File, filePath: other })).valid).toBe(true);
+    logger.info('still applied');
+    logger.shutdown();
+
+    expect(fs.existsSync(other)).toBe(false);
+    expect(fs.readFileSync(filePath, 'utf8')).toContain('still applied');
+  });
+
+  test('should report environment variables by name', () => {
+    process.env.EVENTUM_OUTPUT_BATCH_SIZE = 'many';
+    try {
+      const result = logger.validateConfig(output({ target: logger.OutputTarget.Null }));
+      expect(result.errors).toEqual([
+        { path: 'EVENTUM_OUTPUT_BATCH_SIZE', message: "'many' is not a valid number" }
+      ]);
+    } finally {
+      delete process.env.EVENTUM_OUTPUT_BATCH_SIZE;
+    }
+  });
+
+  test('should throw when there is no config for the environment', () => {
+    expect(() => logger.setConfig({})).toThrow(logger.ConfigValidationError);
+    expect(logger.validateConfig({}).errors).toEqual([{ path: 'dev', message: 'must be set, or prod' }]);
+  });
+
+  test('should throw a ConfigValidationError from loadConfig', () => {
+    const file = path.join(rootDir, 'eventum.json');
+    fs.writeFileSync(file, JSON.stringify(output({ target: 'null', batchIntervalMs: -5 })));
+
+    expect(() => logger.loadConfig(file)).toThrow(logger.ConfigValidationError);
+    expect(() => logger.loadConfig(file)).toThrow(/prod\.output\.batchIntervalMs: must be greater than 0/);
+  });
+});